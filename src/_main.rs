@@ -2,10 +2,31 @@
 // Luma - main.rs
 
 mod vector;
+mod simd;
+mod types;
 mod matrix;
+mod quaternion;
 mod ray;
 mod camera;
 mod raytracer;
+mod shape;
+mod primitive;
+mod instance;
+mod bounds;
+mod polynomial;
+mod csg;
+mod sdf;
+mod animation;
+mod image;
+mod input;
+mod scene;
+mod path;
+mod sampling;
+mod preview;
+mod color;
+mod spectrum;
+mod world;
+mod packet;
 
 use winit::{
     event::*,
@@ -28,10 +49,21 @@ impl LumaApplication
 {
     pub fn new() -> Self
     {
+        let mut raytracer = raytracer::Raytracer::new(1000, 1000);
+
+        // the scene file is optional, so only complain when one exists but cannot be read
+        if let Err(error) = raytracer.load_scene("scene.luma")
+        {
+            if error.kind() != std::io::ErrorKind::NotFound
+            {
+                log::warn!("failed to load scene.luma: {}", error);
+            }
+        }
+
         return Self
         {
             name: "Luma Pathtracer".to_owned(),
-            raytracer: raytracer::Raytracer::new(1000, 1000),
+            raytracer: raytracer,
             texture: None,
             initialized: false,
         }
@@ -112,7 +144,10 @@ pub async fn run()
     }).await.unwrap();
 
     let size = window.inner_size();
-    let surface_format = surface.get_capabilities(&adapter).formats[0];
+    // egui expects a non-srgb surface and the raytraced bitmap arrives already srgb encoded, so avoid the
+    // hardware encoding the first format would often bring
+    let formats = surface.get_capabilities(&adapter).formats;
+    let surface_format = formats.iter().copied().find(|format| !format.is_srgb()).unwrap_or(formats[0]);
     let mut config = wgpu::SurfaceConfiguration
     {
         usage: wgpu::TextureUsages::RENDER_ATTACHMENT,
@@ -152,6 +187,8 @@ pub async fn run()
                         config.width = new_size.width;
                         config.height = new_size.height;
                         surface.configure(&device, &config);
+
+                        app.raytracer.resize(new_size.width as usize, new_size.height as usize);
                     }
                     _ => {}
                 }
@@ -0,0 +1,332 @@
+// (c) 2025 Connor J. Link. All Rights Reserved.
+// Luma - animation.rs
+
+use crate::vector::*;
+use crate::quaternion::*;
+use crate::world::*;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Interpolation
+{
+    Step,
+    Linear,
+    Bezier,
+}
+
+pub trait Animatable: Copy
+{
+    fn lerp(value1: &Self, value2: &Self, t: f32) -> Self;
+}
+
+impl Animatable for f32
+{
+    fn lerp(value1: &f32, value2: &f32, t: f32) -> f32
+    {
+        return value1 + (value2 - value1) * t;
+    }
+}
+
+impl Animatable for Vector
+{
+    fn lerp(value1: &Vector, value2: &Vector, t: f32) -> Vector
+    {
+        return Vector::lerp(value1, value2, t);
+    }
+}
+
+// interpolated in double precision, so keys far from the origin don't jitter in between
+impl Animatable for WorldPosition
+{
+    fn lerp(value1: &WorldPosition, value2: &WorldPosition, t: f32) -> WorldPosition
+    {
+        return WorldPosition::lerp(value1, value2, t);
+    }
+}
+
+impl Animatable for Quaternion
+{
+    fn lerp(value1: &Quaternion, value2: &Quaternion, t: f32) -> Quaternion
+    {
+        return Quaternion::slerp(value1, value2, t);
+    }
+}
+
+#[derive(Clone, Copy)]
+pub struct Keyframe<T: Animatable>
+{
+    pub time: f32,
+    pub value: T,
+
+    // governs the segment from this keyframe to the next one
+    pub interpolation: Interpolation,
+
+    // bezier control values leaving and entering this keyframe
+    pub handle_in: T,
+    pub handle_out: T,
+}
+
+impl<T: Animatable> Keyframe<T>
+{
+    pub fn new(time: f32, value: T, interpolation: Interpolation) -> Keyframe<T>
+    {
+        // handles resting on the value itself produce a smooth ease in/out
+        return Keyframe{ time: time, value: value, interpolation: interpolation, handle_in: value, handle_out: value };
+    }
+
+    pub fn bezier(time: f32, value: T, handle_in: T, handle_out: T) -> Keyframe<T>
+    {
+        return Keyframe{ time: time, value: value, interpolation: Interpolation::Bezier, handle_in: handle_in, handle_out: handle_out };
+    }
+}
+
+#[derive(Clone)]
+pub struct Track<T: Animatable>
+{
+    keys: Vec<Keyframe<T>>,
+}
+
+impl<T: Animatable> Track<T>
+{
+    pub fn new() -> Track<T>
+    {
+        return Track{ keys: Vec::new() };
+    }
+
+    pub fn keys(&self) -> &Vec<Keyframe<T>>
+    {
+        return &self.keys;
+    }
+
+    pub fn is_empty(&self) -> bool
+    {
+        return self.keys.is_empty();
+    }
+
+    pub fn insert(&mut self, key: Keyframe<T>)
+    {
+        // keep the keyframes sorted and replace any keyframe already at this time
+        match self.keys.binary_search_by(|k| k.time.total_cmp(&key.time))
+        {
+            Ok(index) => self.keys[index] = key,
+            Err(index) => self.keys.insert(index, key),
+        }
+    }
+
+    pub fn remove(&mut self, time: f32)
+    {
+        self.keys.retain(|k| k.time != time);
+    }
+
+    pub fn start(&self) -> f32
+    {
+        return self.keys.first().map_or(0.0, |k| k.time);
+    }
+
+    pub fn end(&self) -> f32
+    {
+        return self.keys.last().map_or(0.0, |k| k.time);
+    }
+
+    fn cubic(p0: &T, p1: &T, p2: &T, p3: &T, t: f32) -> T
+    {
+        // de casteljau evaluation only requires lerp
+        let a = T::lerp(p0, p1, t);
+        let b = T::lerp(p1, p2, t);
+        let c = T::lerp(p2, p3, t);
+
+        let d = T::lerp(&a, &b, t);
+        let e = T::lerp(&b, &c, t);
+
+        return T::lerp(&d, &e, t);
+    }
+
+    pub fn sample(&self, time: f32) -> Option<T>
+    {
+        let first = self.keys.first()?;
+        let last = self.keys.last()?;
+
+        // hold the end values outside the keyed range
+        if time <= first.time
+        {
+            return Some(first.value);
+        }
+
+        if time >= last.time
+        {
+            return Some(last.value);
+        }
+
+        let next = self.keys.partition_point(|k| k.time <= time);
+
+        let key1 = &self.keys[next - 1];
+        let key2 = &self.keys[next];
+
+        let t = (time - key1.time) / (key2.time - key1.time);
+
+        let value = match key1.interpolation
+        {
+            Interpolation::Step => key1.value,
+            Interpolation::Linear => T::lerp(&key1.value, &key2.value, t),
+            Interpolation::Bezier => Self::cubic(&key1.value, &key1.handle_out, &key2.handle_in, &key2.value, t),
+        };
+
+        return Some(value);
+    }
+}
+
+#[derive(Clone)]
+pub struct CameraTracks
+{
+    pub position: Track<WorldPosition>,
+    pub yaw: Track<f32>,
+    pub pitch: Track<f32>,
+    pub fov: Track<f32>,
+
+    // overrides yaw and pitch when keyed, and is the only way to animate roll
+    pub orientation: Track<Quaternion>,
+}
+
+#[derive(Clone)]
+pub struct ObjectTracks
+{
+    // index into the scene object list
+    pub object: usize,
+
+    // object-to-world transform components, with rotation as euler angles in radians
+    pub translation: Track<WorldPosition>,
+    pub rotation: Track<Vector>,
+    pub scale: Track<Vector>,
+
+    // takes the place of the euler rotation when keyed, interpolating along the shortest arc
+    pub orientation: Track<Quaternion>,
+
+    pub diffuse: Track<Vector>,
+    pub specular: Track<Vector>,
+    pub emissive: Track<Vector>,
+    pub metallic: Track<f32>,
+    pub roughness: Track<f32>,
+}
+
+impl ObjectTracks
+{
+    pub fn new(object: usize) -> ObjectTracks
+    {
+        return ObjectTracks
+        {
+            object: object,
+            translation: Track::new(),
+            rotation: Track::new(),
+            scale: Track::new(),
+            orientation: Track::new(),
+            diffuse: Track::new(),
+            specular: Track::new(),
+            emissive: Track::new(),
+            metallic: Track::new(),
+            roughness: Track::new(),
+        };
+    }
+}
+
+#[derive(Clone)]
+pub struct Animation
+{
+    pub camera: CameraTracks,
+    pub objects: Vec<ObjectTracks>,
+    pub sun: Track<Vector>,
+}
+
+impl Animation
+{
+    pub fn new() -> Animation
+    {
+        return Animation
+        {
+            camera: CameraTracks{ position: Track::new(), yaw: Track::new(), pitch: Track::new(), fov: Track::new(), orientation: Track::new() },
+            objects: Vec::new(),
+            sun: Track::new(),
+        };
+    }
+
+    pub fn object(&mut self, object: usize) -> &mut ObjectTracks
+    {
+        let index = match self.objects.iter().position(|o| o.object == object)
+        {
+            Some(index) => index,
+            None =>
+            {
+                self.objects.push(ObjectTracks::new(object));
+                self.objects.len() - 1
+            }
+        };
+
+        return &mut self.objects[index];
+    }
+
+    pub fn duration(&self) -> f32
+    {
+        let mut end = f32::max(self.sun.end(), self.camera.position.end());
+        end = f32::max(end, self.camera.yaw.end());
+        end = f32::max(end, self.camera.pitch.end());
+        end = f32::max(end, self.camera.fov.end());
+        end = f32::max(end, self.camera.orientation.end());
+
+        for tracks in &self.objects
+        {
+            end = f32::max(end, tracks.translation.end());
+            end = f32::max(end, tracks.rotation.end());
+            end = f32::max(end, tracks.scale.end());
+            end = f32::max(end, tracks.orientation.end());
+            end = f32::max(end, tracks.diffuse.end());
+            end = f32::max(end, tracks.specular.end());
+            end = f32::max(end, tracks.emissive.end());
+            end = f32::max(end, tracks.metallic.end());
+            end = f32::max(end, tracks.roughness.end());
+        }
+
+        return end;
+    }
+}
+
+pub struct Sequence
+{
+    pub start: u32,
+    pub end: u32,
+    pub fps: f32,
+
+    // accumulated samples per output frame
+    pub samples: u32,
+    pub bounces: u32,
+
+    // output path where a run of '#' is replaced by the zero-padded frame number
+    pub pattern: String,
+}
+
+impl Sequence
+{
+    pub fn new(start: u32, end: u32, fps: f32, pattern: &str) -> Sequence
+    {
+        return Sequence{ start: start, end: end, fps: fps, samples: 64, bounces: 2, pattern: pattern.to_owned() };
+    }
+
+    pub fn time(&self, frame: u32) -> f32
+    {
+        return frame as f32 / self.fps;
+    }
+
+    pub fn filename(&self, frame: u32) -> String
+    {
+        let Some(first) = self.pattern.find('#') else
+        {
+            // no placeholder, so append the frame number before the extension
+            return match self.pattern.rfind('.')
+            {
+                Some(dot) => format!("{}{:04}{}", &self.pattern[..dot], frame, &self.pattern[dot..]),
+                None => format!("{}{:04}", self.pattern, frame),
+            };
+        };
+
+        let width = self.pattern[first..].chars().take_while(|c| *c == '#').count();
+
+        return format!("{}{:0width$}{}", &self.pattern[..first], frame, &self.pattern[first + width..], width = width);
+    }
+}
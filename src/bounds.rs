@@ -0,0 +1,460 @@
+// (c) 2025 Connor J. Link. All Rights Reserved.
+// Luma - bounds.rs
+
+use crate::matrix::*;
+use crate::vector::*;
+use crate::ray::*;
+
+#[derive(Clone)]
+#[derive(Copy)]
+#[derive(Debug)]
+pub struct Aabb
+{
+    pub min: Vector,
+    pub max: Vector,
+}
+
+impl Aabb
+{
+    pub fn new(min: Vector, max: Vector) -> Aabb
+    {
+        return Aabb{ min: min, max: max };
+    }
+
+    pub fn empty() -> Aabb
+    {
+        return Aabb{ min: Vector::new(f32::MAX, f32::MAX, f32::MAX, 1.0), max: Vector::new(-f32::MAX, -f32::MAX, -f32::MAX, 1.0) };
+    }
+
+    pub fn infinite() -> Aabb
+    {
+        return Aabb{ min: Vector::new(-f32::INFINITY, -f32::INFINITY, -f32::INFINITY, 1.0), max: Vector::new(f32::INFINITY, f32::INFINITY, f32::INFINITY, 1.0) };
+    }
+
+    pub fn is_finite(&self) -> bool
+    {
+        let min = self.min;
+        let max = self.max;
+
+        return min.x().is_finite() && min.y().is_finite() && min.z().is_finite() && max.x().is_finite() && max.y().is_finite() && max.z().is_finite();
+    }
+
+    pub fn center(&self) -> Vector
+    {
+        let center = Vector::scale(&Vector::add(&self.min, &self.max), 0.5);
+        return Vector::new(center.x(), center.y(), center.z(), 1.0);
+    }
+
+    pub fn extent(&self) -> Vector
+    {
+        return Vector::sub(&self.max, &self.min);
+    }
+
+    // true for empty() and for any box left inverted by an intersection of disjoint boxes
+    pub fn is_empty(&self) -> bool
+    {
+        return self.min.x() > self.max.x() || self.min.y() > self.max.y() || self.min.z() > self.max.z();
+    }
+
+    // the cost metric of the surface area heuristic, proportional to the chance a random ray hits the box
+    pub fn surface_area(&self) -> f32
+    {
+        if self.is_empty()
+        {
+            return 0.0;
+        }
+
+        let extent = self.extent();
+        return 2.0 * (extent.x() * extent.y() + extent.y() * extent.z() + extent.z() * extent.x());
+    }
+
+    pub fn volume(&self) -> f32
+    {
+        if self.is_empty()
+        {
+            return 0.0;
+        }
+
+        let extent = self.extent();
+        return extent.x() * extent.y() * extent.z();
+    }
+
+    // index of the widest axis, which is where a bvh build would usually split
+    pub fn longest_axis(&self) -> usize
+    {
+        let extent = self.extent();
+
+        if extent.x() >= extent.y() && extent.x() >= extent.z()
+        {
+            return 0;
+        }
+
+        return if extent.y() >= extent.z() { 1 } else { 2 };
+    }
+
+    pub fn contains(&self, point: &Vector) -> bool
+    {
+        return point.x() >= self.min.x() && point.y() >= self.min.y() && point.z() >= self.min.z()
+            && point.x() <= self.max.x() && point.y() <= self.max.y() && point.z() <= self.max.z();
+    }
+
+    pub fn closest_point(&self, point: &Vector) -> Vector
+    {
+        let x = f32::clamp(point.x(), self.min.x(), self.max.x());
+        let y = f32::clamp(point.y(), self.min.y(), self.max.y());
+        let z = f32::clamp(point.z(), self.min.z(), self.max.z());
+
+        return Vector::new(x, y, z, 1.0);
+    }
+
+    // grows every face outwards by the same margin, or shrinks it for a negative one
+    pub fn expand(box1: &Aabb, margin: f32) -> Aabb
+    {
+        let margin = Vector::new(margin, margin, margin, 0.0);
+        return Aabb::new(Vector::sub(&box1.min, &margin), Vector::add(&box1.max, &margin));
+    }
+
+    pub fn include(box1: &Aabb, point: &Vector) -> Aabb
+    {
+        let min = Vector::new(f32::min(box1.min.x(), point.x()), f32::min(box1.min.y(), point.y()), f32::min(box1.min.z(), point.z()), 1.0);
+        let max = Vector::new(f32::max(box1.max.x(), point.x()), f32::max(box1.max.y(), point.y()), f32::max(box1.max.z(), point.z()), 1.0);
+
+        return Aabb{ min: min, max: max };
+    }
+
+    pub fn union(box1: &Aabb, box2: &Aabb) -> Aabb
+    {
+        let expanded = Aabb::include(box1, &box2.min);
+        return Aabb::include(&expanded, &box2.max);
+    }
+
+    pub fn overlaps(box1: &Aabb, box2: &Aabb) -> bool
+    {
+        return !Aabb::intersection(box1, box2).is_empty();
+    }
+
+    pub fn intersection(box1: &Aabb, box2: &Aabb) -> Aabb
+    {
+        let min = Vector::new(f32::max(box1.min.x(), box2.min.x()), f32::max(box1.min.y(), box2.min.y()), f32::max(box1.min.z(), box2.min.z()), 1.0);
+        let max = Vector::new(f32::min(box1.max.x(), box2.max.x()), f32::min(box1.max.y(), box2.max.y()), f32::min(box1.max.z(), box2.max.z()), 1.0);
+
+        return Aabb{ min: min, max: max };
+    }
+
+    pub fn transform(box1: &Aabb, mat1: &Matrix) -> Aabb
+    {
+        if !box1.is_finite()
+        {
+            return Aabb::infinite();
+        }
+
+        let mut result = Aabb::empty();
+
+        for corner in 0..8
+        {
+            let x = if corner & 1 == 0 { box1.min.x() } else { box1.max.x() };
+            let y = if corner & 2 == 0 { box1.min.y() } else { box1.max.y() };
+            let z = if corner & 4 == 0 { box1.min.z() } else { box1.max.z() };
+
+            let transformed = Matrix::transform_point(mat1, &Vector::new(x, y, z, 1.0));
+            result = Aabb::include(&result, &transformed);
+        }
+
+        return result;
+    }
+
+    // slab test returning the entry and exit distances along the whole line
+    pub fn intersect(box1: &Aabb, ray: &Ray) -> Option<(f32, f32)>
+    {
+        let origin = ray.origin.raw();
+        let direction = ray.direction.raw();
+        let min = box1.min.raw();
+        let max = box1.max.raw();
+
+        let mut near = -f32::MAX;
+        let mut far = f32::MAX;
+
+        for axis in 0..3
+        {
+            let inverse = 1.0 / direction[axis];
+
+            let mut t1 = (min[axis] - origin[axis]) * inverse;
+            let mut t2 = (max[axis] - origin[axis]) * inverse;
+
+            if t1 > t2
+            {
+                std::mem::swap(&mut t1, &mut t2);
+            }
+
+            // f32::max and f32::min discard the nan produced by a parallel ray on a slab plane
+            near = f32::max(near, t1);
+            far = f32::min(far, t2);
+
+            if near > far
+            {
+                return None;
+            }
+        }
+
+        return Some((near, far));
+    }
+}
+
+#[derive(Clone)]
+#[derive(Copy)]
+#[derive(Debug)]
+pub struct BoundingSphere
+{
+    pub center: Vector,
+    pub radius: f32,
+}
+
+impl BoundingSphere
+{
+    pub fn new(center: Vector, radius: f32) -> BoundingSphere
+    {
+        return BoundingSphere{ center: center, radius: radius };
+    }
+
+    // circumscribes the box, which is looser than the tightest sphere but needs no search
+    pub fn from_aabb(box1: &Aabb) -> BoundingSphere
+    {
+        return BoundingSphere{ center: box1.center(), radius: 0.5 * Vector::length(&box1.extent()) };
+    }
+
+    // ritter's approximation, usually within a few percent of the minimal sphere
+    pub fn from_points(points: &[Vector]) -> BoundingSphere
+    {
+        let Some(first) = points.first() else
+        {
+            return BoundingSphere{ center: Vector::new(0.0, 0.0, 0.0, 1.0), radius: 0.0 };
+        };
+
+        let farthest = |from: &Vector| *points.iter().max_by(|a, b| Vector::length(&Vector::sub(a, from)).total_cmp(&Vector::length(&Vector::sub(b, from)))).unwrap_or(from);
+
+        // start from a sphere spanning two mutually distant points
+        let a = farthest(first);
+        let b = farthest(&a);
+
+        let mut center = Vector::scale(&Vector::add(&a, &b), 0.5);
+        let mut radius = 0.5 * Vector::length(&Vector::sub(&b, &a));
+
+        // then grow it just enough to take in any point still outside
+        for point in points
+        {
+            let offset = Vector::sub(point, &center);
+            let distance = Vector::length(&offset);
+
+            if distance > radius
+            {
+                let grown = 0.5 * (radius + distance);
+                center = Vector::add(&center, &Vector::scale(&offset, (grown - radius) / distance));
+                radius = grown;
+            }
+        }
+
+        return BoundingSphere{ center: Vector::new(center.x(), center.y(), center.z(), 1.0), radius: radius };
+    }
+
+    pub fn to_aabb(&self) -> Aabb
+    {
+        return Aabb::expand(&Aabb::new(self.center, self.center), self.radius);
+    }
+
+    pub fn contains(&self, point: &Vector) -> bool
+    {
+        return Vector::length(&Vector::sub(point, &self.center)) <= self.radius;
+    }
+
+    pub fn union(sphere1: &BoundingSphere, sphere2: &BoundingSphere) -> BoundingSphere
+    {
+        let offset = Vector::sub(&sphere2.center, &sphere1.center);
+        let distance = Vector::length(&offset);
+
+        // one already encloses the other
+        if distance + sphere2.radius <= sphere1.radius
+        {
+            return *sphere1;
+        }
+
+        if distance + sphere1.radius <= sphere2.radius
+        {
+            return *sphere2;
+        }
+
+        let radius = 0.5 * (distance + sphere1.radius + sphere2.radius);
+        let center = Vector::add(&sphere1.center, &Vector::scale(&offset, (radius - sphere1.radius) / distance));
+
+        return BoundingSphere{ center: center, radius: radius };
+    }
+
+    // scales the radius by the largest axis scale so the result still encloses the transformed sphere
+    pub fn transform(sphere1: &BoundingSphere, mat1: &Matrix) -> BoundingSphere
+    {
+        let center = Matrix::transform_point(mat1, &sphere1.center);
+
+        let mut scale: f32 = 0.0;
+
+        for axis in [Vector::new(1.0, 0.0, 0.0, 0.0), Vector::new(0.0, 1.0, 0.0, 0.0), Vector::new(0.0, 0.0, 1.0, 0.0)]
+        {
+            scale = f32::max(scale, Vector::length(&Matrix::transform_direction(mat1, &axis)));
+        }
+
+        return BoundingSphere{ center: center, radius: sphere1.radius * scale };
+    }
+
+    pub fn overlaps(sphere1: &BoundingSphere, sphere2: &BoundingSphere) -> bool
+    {
+        return Vector::length(&Vector::sub(&sphere2.center, &sphere1.center)) <= sphere1.radius + sphere2.radius;
+    }
+
+    pub fn overlaps_aabb(sphere1: &BoundingSphere, box1: &Aabb) -> bool
+    {
+        let closest = box1.closest_point(&sphere1.center);
+        return Vector::length(&Vector::sub(&closest, &sphere1.center)) <= sphere1.radius;
+    }
+
+    // entry and exit distances along the whole line, like Aabb::intersect
+    pub fn intersect(sphere1: &BoundingSphere, ray: &Ray) -> Option<(f32, f32)>
+    {
+        let offset = Vector::sub(&ray.origin, &sphere1.center);
+
+        let a = Vector::dot(&ray.direction, &ray.direction);
+        let b = Vector::dot(&offset, &ray.direction);
+        let c = Vector::dot(&offset, &offset) - sphere1.radius * sphere1.radius;
+
+        let discriminant = b * b - a * c;
+
+        if a == 0.0 || discriminant < 0.0
+        {
+            return None;
+        }
+
+        let root = f32::sqrt(discriminant);
+        return Some(((-b - root) / a, (-b + root) / a));
+    }
+}
+
+// the points p with dot(normal, p) + distance >= 0, so the normal points into the kept side
+#[derive(Clone)]
+#[derive(Copy)]
+#[derive(Debug)]
+pub struct Halfspace
+{
+    pub normal: Vector,
+    pub distance: f32,
+}
+
+impl Halfspace
+{
+    pub fn new(normal: Vector, distance: f32) -> Halfspace
+    {
+        let length = Vector::length(&normal);
+
+        if length == 0.0
+        {
+            return Halfspace{ normal: Vector::zero(), distance: distance };
+        }
+
+        // unit normals make signed_distance a true distance
+        let normal = Vector::new(normal.x() / length, normal.y() / length, normal.z() / length, 0.0);
+        return Halfspace{ normal: normal, distance: distance / length };
+    }
+
+    pub fn from_point(point: &Vector, normal: &Vector) -> Halfspace
+    {
+        let normal = Vector::normalize(&Vector::new(normal.x(), normal.y(), normal.z(), 0.0));
+        return Halfspace{ normal: normal, distance: -Vector::dot(&normal, &Vector::new(point.x(), point.y(), point.z(), 0.0)) };
+    }
+
+    pub fn signed_distance(&self, point: &Vector) -> f32
+    {
+        return self.normal.x() * point.x() + self.normal.y() * point.y() + self.normal.z() * point.z() + self.distance;
+    }
+
+    pub fn contains(&self, point: &Vector) -> bool
+    {
+        return self.signed_distance(point) >= 0.0;
+    }
+
+    // distance along the ray to the boundary plane, which may be negative, or none when parallel
+    pub fn intersect(&self, ray: &Ray) -> Option<f32>
+    {
+        let denominator = Vector::dot(&self.normal, &Vector::new(ray.direction.x(), ray.direction.y(), ray.direction.z(), 0.0));
+
+        if denominator == 0.0
+        {
+            return None;
+        }
+
+        return Some(-self.signed_distance(&ray.origin) / denominator);
+    }
+
+    pub fn overlaps_sphere(&self, sphere1: &BoundingSphere) -> bool
+    {
+        return self.signed_distance(&sphere1.center) >= -sphere1.radius;
+    }
+
+    pub fn overlaps_aabb(&self, box1: &Aabb) -> bool
+    {
+        // only the corner furthest along the normal needs testing
+        let x = if self.normal.x() >= 0.0 { box1.max.x() } else { box1.min.x() };
+        let y = if self.normal.y() >= 0.0 { box1.max.y() } else { box1.min.y() };
+        let z = if self.normal.z() >= 0.0 { box1.max.z() } else { box1.min.z() };
+
+        return self.signed_distance(&Vector::new(x, y, z, 1.0)) >= 0.0;
+    }
+}
+
+// the convex volume seen through a projection, as six inward-facing halfspaces
+#[derive(Clone)]
+#[derive(Copy)]
+#[derive(Debug)]
+pub struct Frustum
+{
+    pub planes: [Halfspace; 6],
+}
+
+impl Frustum
+{
+    // gribb and hartmann's extraction from a world-to-clip matrix, e.g. view times projection. with row vectors the
+    // clip coordinates are dot products against the columns, and each plane bounds one of -w <= x, y, z <= w
+    pub fn from_matrix(mat1: &Matrix) -> Frustum
+    {
+        let column = |j: usize| [mat1.get(0, j), mat1.get(1, j), mat1.get(2, j), mat1.get(3, j)];
+
+        let x = column(0);
+        let y = column(1);
+        let z = column(2);
+        let w = column(3);
+
+        let plane = |sign: f32, c: [f32; 4]| Halfspace::new(Vector::new(w[0] + sign * c[0], w[1] + sign * c[1], w[2] + sign * c[2], 0.0), w[3] + sign * c[3]);
+
+        return Frustum
+        {
+            // left, right, bottom, top, near, far
+            planes: [plane(1.0, x), plane(-1.0, x), plane(1.0, y), plane(-1.0, y), plane(1.0, z), plane(-1.0, z)],
+        };
+    }
+
+    pub fn contains(&self, point: &Vector) -> bool
+    {
+        return self.planes.iter().all(|plane| plane.contains(point));
+    }
+
+    // conservative: may accept a volume just outside a corner of the frustum, but never rejects a visible one
+    pub fn overlaps_sphere(&self, sphere1: &BoundingSphere) -> bool
+    {
+        return self.planes.iter().all(|plane| plane.overlaps_sphere(sphere1));
+    }
+
+    pub fn overlaps_aabb(&self, box1: &Aabb) -> bool
+    {
+        if !box1.is_finite()
+        {
+            return true;
+        }
+
+        return self.planes.iter().all(|plane| plane.overlaps_aabb(box1));
+    }
+}
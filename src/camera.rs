@@ -2,8 +2,79 @@
 // Luma - camera.rs
 
 use crate::matrix::*;
+use crate::quaternion::*;
 use crate::vector::*;
+use crate::types::*;
 use crate::ray::*;
+use crate::bounds::*;
+use crate::input::*;
+
+use std::f32::consts::PI;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Projection
+{
+    Perspective,
+
+    // parallel rays through a view volume of the given height in world units
+    Orthographic{ height: f32 },
+
+    // equidistant fisheye mapping the image circle onto the given field of view in degrees
+    Fisheye{ fov: f32 },
+
+    // full 360 by 180 degree latitude-longitude panorama
+    Equirectangular,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum StereoLayout
+{
+    // left eye in the left half of the frame and right eye in the right half
+    SideBySide,
+
+    // left eye in the top half of the frame and right eye in the bottom half
+    OverUnder,
+
+    // both eyes over the full frame, combined into red and cyan channels
+    Anaglyph,
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct Stereo
+{
+    pub layout: StereoLayout,
+
+    // distance between the eyes in world units
+    pub interocular: f32,
+
+    // distance to the plane where both eyes agree, with zero parallax
+    pub convergence: f32,
+}
+
+impl Stereo
+{
+    pub fn new(layout: StereoLayout) -> Stereo
+    {
+        return Stereo{ layout: layout, interocular: 0.064, convergence: 5.0 };
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Eye
+{
+    Left,
+    Right,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Controller
+{
+    // wasd translation with arrow keys and primary drag to look around
+    FreeFly,
+
+    // primary drag rotates around the target, middle drag pans and the wheel dollies
+    Orbit,
+}
 
 pub struct Camera
 {
@@ -14,16 +85,23 @@ pub struct Camera
     view_inverse: Matrix,
 
     // camera configuration
+    mode: Projection,
+    stereo: Option<Stereo>,
     fov: f32,
     near: f32,
     far: f32,
     width: usize,
     height: usize,
 
-    // camera look
+    // camera look, as a rotation from looking down +z with +y up. yaw, pitch and roll are only derived for display,
+    // since angles stop describing the orientation uniquely at straight up or down
     pub moved: bool,
-    pitch: f32,
-    yaw: f32,
+    orientation: Quaternion,
+
+    // camera controls
+    controller: Controller,
+    target: Vector,
+    distance: f32,
 
     // depth-of-field effect
     depth: f32,
@@ -34,8 +112,9 @@ pub struct Camera
     direction: Vector,
     right: Vector,
 
-    // camera view
+    // camera view, with the right eye of an anaglyph kept separately
     rays: Vec<Ray>,
+    partner_rays: Vec<Ray>,
 }
 
 impl Camera
@@ -48,20 +127,25 @@ impl Camera
             projection_inverse: Matrix::identity(),
             view: Matrix::identity(),
             view_inverse: Matrix::identity(),
+            mode: Projection::Perspective,
+            stereo: None,
             fov: fov,
             near: near,
             far: far,
             width: width,
             height: height,
             moved: false,
-            pitch: 0.0,
-            yaw: 0.0,
+            orientation: Quaternion::identity(),
+            controller: Controller::FreeFly,
+            target: Vector::new(0.0, 0.0, 5.0, 1.0),
+            distance: 5.0,
             depth: 10.0,
             show_depth: false,
             position: Vector::zero(),
             direction: Vector::new(0.0, 0.0, 1.0, 1.0),
             right: Vector::zero(),
             rays: vec![],
+            partner_rays: vec![],
         };
 
         camera.recompute_projection();
@@ -76,6 +160,44 @@ impl Camera
         return &self.rays;
     }
 
+    // right eye rays matching rays() pixel for pixel, empty unless rendering an anaglyph
+    pub fn partner_rays(&self) -> &Vec<Ray>
+    {
+        return &self.partner_rays;
+    }
+
+    pub fn stereo(&self) -> Option<Stereo>
+    {
+        return self.stereo;
+    }
+
+    pub fn set_stereo(&mut self, stereo: Option<Stereo>)
+    {
+        self.stereo = stereo;
+
+        self.recompute_projection();
+        self.recompute_rays();
+
+        self.moved = true;
+    }
+
+    // size of the image seen by a single eye
+    fn eye_size(&self) -> (usize, usize)
+    {
+        return match self.stereo.map(|s| s.layout)
+        {
+            Some(StereoLayout::SideBySide) => (usize::max(self.width / 2, 1), self.height),
+            Some(StereoLayout::OverUnder) => (self.width, usize::max(self.height / 2, 1)),
+            _ => (self.width, self.height),
+        };
+    }
+
+    fn aspect_ratio(&self) -> f32
+    {
+        let (width, height) = self.eye_size();
+        return width as f32 / height as f32;
+    }
+
     pub fn view(&self) -> &Matrix
     {
         return &self.view;
@@ -86,6 +208,17 @@ impl Camera
         return &self.view_inverse;
     }
 
+    pub fn projection(&self) -> &Matrix
+    {
+        return &self.projection;
+    }
+
+    // world-space view volume, for culling anything that can't reach the image
+    pub fn frustum(&self) -> Frustum
+    {
+        return Frustum::from_matrix(&Matrix::mul(&self.view, &self.projection));
+    }
+
     pub fn position(&self) -> Vector
     {
         return self.position;
@@ -106,164 +239,830 @@ impl Camera
         return self.height;
     }
 
+    pub fn resize(&mut self, width: usize, height: usize)
+    {
+        if width == self.width && height == self.height
+        {
+            return;
+        }
+
+        self.width = usize::max(width, 1);
+        self.height = usize::max(height, 1);
+
+        self.recompute_projection();
+        self.recompute_rays();
+
+        self.moved = true;
+    }
+
+    // roll about the view axis, then pitch about x, then yaw about y
+    pub fn compose(yaw: f32, pitch: f32, roll: f32) -> Quaternion
+    {
+        let roll = Quaternion::axis_angle(&Vector::new(0.0, 0.0, 1.0, 0.0), roll);
+        let pitch = Quaternion::axis_angle(&Vector::new(1.0, 0.0, 0.0, 0.0), pitch);
+        let yaw = Quaternion::axis_angle(&Vector::new(0.0, 1.0, 0.0, 0.0), yaw);
+
+        return Quaternion::mul(&Quaternion::mul(&roll, &pitch), &yaw);
+    }
+
+    // inverts compose, with the heading folded into the roll when looking straight up or down
+    fn angles(&self) -> (f32, f32, f32)
+    {
+        let forward = Quaternion::rotate(&self.orientation, &Vector::new(0.0, 0.0, 1.0, 0.0));
+
+        // equivalent to (cos pitch sin yaw, -sin pitch, cos pitch cos yaw)
+        let yaw = f32::atan2(forward.x(), forward.z());
+        let pitch = -f32::asin(f32::clamp(forward.y(), -1.0, 1.0));
+
+        // undo the yaw and pitch so only the roll about z is left acting on the up vector
+        let turned = Self::compose(yaw, pitch, 0.0);
+        let unturned = Quaternion::mul(&self.orientation, &Quaternion::conjugate(&turned));
+        let up = Quaternion::rotate(&unturned, &Vector::new(0.0, 1.0, 0.0, 0.0));
+
+        return (yaw, pitch, f32::atan2(-up.x(), up.y()));
+    }
+
+    pub fn yaw(&self) -> f32
+    {
+        return self.angles().0;
+    }
+
+    pub fn pitch(&self) -> f32
+    {
+        return self.angles().1;
+    }
+
+    pub fn roll(&self) -> f32
+    {
+        return self.angles().2;
+    }
+
+    pub fn set_roll(&mut self, roll: f32)
+    {
+        // turns about the view axis by the difference, which stays well defined when looking straight up or down
+        let turn = Quaternion::axis_angle(&Vector::new(0.0, 0.0, 1.0, 0.0), roll - self.roll());
+        self.orientation = Quaternion::normalize(&Quaternion::mul(&turn, &self.orientation));
+
+        self.recompute_direction();
+        self.recompute_view();
+        self.recompute_rays();
+
+        self.moved = true;
+    }
+
+    pub fn orientation(&self) -> Quaternion
+    {
+        return self.orientation;
+    }
+
+    pub fn set_orientation(&mut self, orientation: &Quaternion)
+    {
+        self.set_transform(self.position, orientation);
+    }
+
+    // places the camera from a world position and orientation, such as a decomposed object transform
+    pub fn set_transform(&mut self, position: Vector, orientation: &Quaternion)
+    {
+        self.position = position;
+        self.orientation = Quaternion::normalize(orientation);
+
+        self.recompute_direction();
+        self.recompute_view();
+        self.recompute_rays();
+
+        self.moved = true;
+    }
+
+    pub fn fov(&self) -> f32
+    {
+        return self.fov;
+    }
+
+    // distance to the plane in focus for the depth-of-field effect
+    pub fn focus(&self) -> f32
+    {
+        return self.depth;
+    }
+
+    pub fn set_focus(&mut self, focus: f32)
+    {
+        self.depth = f32::max(focus, self.near);
+        self.moved = true;
+    }
+
+    pub fn set_pose(&mut self, position: Vector, yaw: f32, pitch: f32)
+    {
+        self.position = position;
+        self.orientation = Self::compose(yaw, pitch, self.roll());
+
+        self.recompute_direction();
+        self.recompute_view();
+        self.recompute_rays();
+
+        self.moved = true;
+    }
+
+    pub fn controller(&self) -> Controller
+    {
+        return self.controller;
+    }
+
+    pub fn set_controller(&mut self, controller: Controller)
+    {
+        if controller == Controller::Orbit && self.controller != Controller::Orbit
+        {
+            // orbit around whatever currently sits in front of the camera, after any turn earlier in the batch
+            self.recompute_direction();
+            self.target = self.position + self.direction * self.distance;
+        }
+
+        self.controller = controller;
+    }
+
+    pub fn look_at(&mut self, target: Vector)
+    {
+        self.aim(target);
+
+        self.recompute_direction();
+        self.recompute_view();
+        self.recompute_rays();
+
+        self.moved = true;
+    }
+
+    // turns towards the target without recomputing anything
+    fn aim(&mut self, target: Vector)
+    {
+        let offset = target - self.position;
+        let length = Vector::length(&offset);
+
+        if length == 0.0
+        {
+            return;
+        }
+
+        // inverts the direction formula in angles
+        let direction = offset / length;
+
+        let yaw = f32::atan2(direction.x(), direction.z());
+        let pitch = -f32::asin(f32::clamp(direction.y(), -1.0, 1.0));
+
+        self.orientation = Self::compose(yaw, pitch, self.roll());
+        self.target = target;
+        self.distance = length;
+    }
+
+    pub fn target(&self) -> Vector
+    {
+        return self.target;
+    }
+
+    // moves the camera back along its view direction until the sphere fills the field of view
+    pub fn frame(&mut self, center: Vector, radius: f32)
+    {
+        self.fit(center, radius);
+
+        self.recompute_view();
+        self.recompute_rays();
+
+        self.moved = true;
+    }
+
+    // backs away from the sphere without recomputing the view or rays
+    fn fit(&mut self, center: Vector, radius: f32)
+    {
+        let half_fov = 0.5 * f32::to_radians(self.fov);
+
+        // any turn earlier in the same batch has to count
+        self.recompute_direction();
+
+        self.target = center;
+        self.distance = f32::max(radius / f32::sin(half_fov), self.near);
+        self.position = self.target - self.direction * self.distance;
+    }
+
+    // follows a floating origin move, which leaves the view itself unchanged
+    pub fn rebase(&mut self, shift: &Vector)
+    {
+        self.position -= *shift;
+        self.target -= *shift;
+
+        self.recompute_view();
+        self.recompute_rays();
+
+        self.moved = true;
+    }
+
+    pub fn projection_mode(&self) -> Projection
+    {
+        return self.mode;
+    }
+
+    pub fn set_projection_mode(&mut self, mode: Projection)
+    {
+        self.mode = mode;
+
+        self.recompute_projection();
+        self.recompute_rays();
+
+        self.moved = true;
+    }
+
+    pub fn set_fov(&mut self, fov: f32)
+    {
+        self.fov = fov;
+
+        self.recompute_projection();
+        self.recompute_rays();
+
+        self.moved = true;
+    }
+
+    pub fn recompute_direction(&mut self)
+    {
+        let forward = Quaternion::rotate(&self.orientation, &Vector::new(0.0, 0.0, 1.0, 0.0));
+        self.direction = Vector::normalize(&forward);
+    }
+
     pub fn recompute_view(&mut self)
     {
-        let at = Vector::add(&self.position, &self.direction);
-        let up = Vector::new(0.0, 1.0, 0.0, 1.0);
+        let at = self.position + self.direction;
+
+        // the up vector follows the roll, and stays well defined when looking straight up or down
+        let up = Quaternion::rotate(&self.orientation, &Vector::new(0.0, 1.0, 0.0, 0.0));
 
         self.view = Matrix::lookat(&self.position, &at, &up);
-        self.view_inverse = Matrix::inverse(&self.view);
+
+        // a view matrix is always rigid, so the cheap affine inverse applies
+        match Matrix::affine_inverse(&self.view)
+        {
+            Some(inverse) => self.view_inverse = inverse,
+            None => log::warn!("camera view matrix is not invertible, keeping the previous inverse"),
+        }
     }
 
     pub fn recompute_projection(&mut self)
     {
-        let aspect_ratio = self.width as f32 / self.height as f32;
+        let aspect_ratio = self.aspect_ratio();
 
         let fov_radians = f32::to_radians(self.fov);
 
-        self.projection = Matrix::perspective(fov_radians, aspect_ratio, self.near, self.far);
-        self.projection_inverse = Matrix::inverse(&self.projection);
+        self.projection = match self.mode
+        {
+            Projection::Orthographic{ height } =>
+            {
+                let half_height = 0.5 * height;
+                let half_width = half_height * aspect_ratio;
+
+                Matrix::orthographic(-half_width, half_width, -half_height, half_height, self.near, self.far)
+            }
+
+            // the non-linear projections are built per ray, so keep a perspective matrix for anything else that needs one
+            _ => Matrix::perspective(fov_radians, aspect_ratio, self.near, self.far),
+        };
+
+        // a degenerate projection, such as a zero fov or an empty viewport, keeps the last usable inverse
+        match Matrix::inverse(&self.projection)
+        {
+            Some(inverse) => self.projection_inverse = inverse,
+            None => log::warn!("camera projection matrix is not invertible, keeping the previous inverse"),
+        }
     }
 
-    pub fn recompute_rays(&mut self)
+    // view-space ray through a point in normalized device coordinates, or none outside the projection
+    fn project(&self, x_coord: f32, y_coord: f32) -> Option<Ray>
     {
-        self.rays.resize(self.width * self.height, Ray::zero());
+        let aspect_ratio = self.aspect_ratio();
+        let origin = Vector::new(0.0, 0.0, 0.0, 1.0);
 
-        for y in 0..self.height
+        match self.mode
         {
-            for x in 0..self.width
+            Projection::Perspective =>
             {
-                let x_coord = (x as f32 / self.width  as f32) * 2.0 - 1.0;
-                let y_coord = (y as f32 / self.height as f32) * 2.0 - 1.0;
+                let target = Matrix::transform_point(&self.projection_inverse, &Vector::new(x_coord, y_coord, 1.0, 1.0));
+                let direction = Vector::normalize(&Vector::new(target.x(), target.y(), target.z(), 0.0));
 
-                let extended: Vector = Vector::new(x_coord, y_coord, 1.0, 1.0);
-                let target = Matrix::apply(&self.projection_inverse, &extended);
+                return Some(Ray::new(origin, direction));
+            }
 
-                let homogenized = Vector::scale(&target, 1.0 / target.w());
-                
-                let mut normalized = Vector::normalize(&homogenized);
-                normalized.set_w(0.0);
+            Projection::Orthographic{ .. } =>
+            {
+                // every ray starts on the near plane and runs parallel to the view axis
+                let near = Matrix::transform_point(&self.projection_inverse, &Vector::new(x_coord, y_coord, -1.0, 1.0));
+                let far = Matrix::transform_point(&self.projection_inverse, &Vector::new(x_coord, y_coord, 1.0, 1.0));
 
-                let direction = Matrix::apply(&self.view_inverse, &normalized);
-                let position = self.position;
+                return Some(Ray::new(near, Vector::normalize(&(far - near))));
+            }
 
-                let ray = Ray::new(position, direction);
-                self.rays[y * self.width + x] = ray;
+            Projection::Fisheye{ fov } =>
+            {
+                // the image circle fits the shorter side of the frame
+                let u = x_coord * f32::max(aspect_ratio, 1.0);
+                let v = y_coord / f32::min(aspect_ratio, 1.0);
+
+                let r = f32::sqrt(u * u + v * v);
+
+                if r > 1.0
+                {
+                    return None;
+                }
+
+                let theta = r * 0.5 * f32::to_radians(fov);
+
+                if r == 0.0
+                {
+                    return Some(Ray::new(origin, Vector::new(0.0, 0.0, -1.0, 0.0)));
+                }
+
+                let s = f32::sin(theta) / r;
+                return Some(Ray::new(origin, Vector::new(u * s, v * s, -f32::cos(theta), 0.0)));
+            }
+
+            Projection::Equirectangular =>
+            {
+                let longitude = x_coord * PI;
+                let latitude = y_coord * 0.5 * PI;
+
+                let direction = Vector::new
+                (
+                    f32::cos(latitude) * f32::sin(longitude),
+                    f32::sin(latitude),
+                    -f32::cos(latitude) * f32::cos(longitude),
+                    0.0,
+                );
+
+                return Some(Ray::new(origin, direction));
             }
         }
     }
 
-    pub fn update(&mut self, delta: f32, ctx: &egui::Context)
+    // moves a view-space ray over to one eye of the stereo rig
+    fn offset_eye(&self, ray: &Ray, stereo: &Stereo, eye: Eye) -> Ray
     {
-        const MOVEMENT_SPEED: f32 = 0.75;
-        const ROTATION_SPEED: f32 = 1.0;
-
-        self.moved = false;
+        let sign = match eye
+        {
+            Eye::Left => -1.0,
+            Eye::Right => 1.0,
+        };
 
-        let up = Vector::new(0.0, 1.0, 0.0, 1.0);
-        let dir = Vector::new(self.direction.x(), 0.0, -self.direction.z(), 1.0);
-        let forward = Vector::normalize(&dir);
-        let right = Vector::cross(&up, &forward);
+        let half = 0.5 * stereo.interocular * sign;
 
-        if ctx.input(|i| i.key_pressed(egui::Key::W))
+        match self.mode
         {
-            self.position = Vector::add(&self.position, &Vector::scale(&forward, MOVEMENT_SPEED * delta));
-            self.moved = true;
+            Projection::Perspective | Projection::Orthographic{ .. } =>
+            {
+                // off-axis frustums: both eyes aim at where the centre ray meets the convergence plane
+                let forward = -ray.direction.z();
+
+                if forward <= 0.0
+                {
+                    return Ray::new(ray.origin + Vector::new(half, 0.0, 0.0, 0.0), ray.direction);
+                }
+
+                let focus = ray.origin + ray.direction * (stereo.convergence / forward);
+                let origin = ray.origin + Vector::new(half, 0.0, 0.0, 0.0);
+
+                return Ray::new(origin, Vector::normalize(&(focus - origin)));
+            }
+
+            Projection::Fisheye{ .. } | Projection::Equirectangular =>
+            {
+                // omnidirectional stereo: each eye sits on a circle, offset at right angles to the horizontal ray direction
+                let horizontal = f32::sqrt(ray.direction.x() * ray.direction.x() + ray.direction.z() * ray.direction.z());
+
+                if horizontal == 0.0
+                {
+                    return *ray;
+                }
+
+                let tangent = Vector::new(-ray.direction.z() / horizontal, 0.0, ray.direction.x() / horizontal, 0.0);
+                let origin = ray.origin + tangent * half;
+
+                return Ray::new(origin, ray.direction);
+            }
         }
+    }
+
+    // view-space ray for a pixel of one eye's image, or none outside the projection
+    fn eye_ray(&self, x: usize, y: usize, eye: Option<Eye>) -> Option<Ray>
+    {
+        let (width, height) = self.eye_size();
+
+        let x_coord = (x as f32 / width  as f32) * 2.0 - 1.0;
+        let y_coord = (y as f32 / height as f32) * 2.0 - 1.0;
 
-        else if ctx.input(|i| i.key_pressed(egui::Key::S))
+        let local = self.project(x_coord, y_coord)?;
+
+        return match (self.stereo, eye)
         {
-            self.position = Vector::sub(&self.position, &Vector::scale(&forward, MOVEMENT_SPEED * delta));
-            self.moved = true;
-        }
+            (Some(stereo), Some(eye)) => Some(self.offset_eye(&local, &stereo, eye)),
+            _ => Some(local),
+        };
+    }
 
-        if ctx.input(|i| i.key_pressed(egui::Key::A))
+    fn to_world(&self, local: Option<Ray>) -> Ray
+    {
+        // pixels outside the projection keep a zero direction and render black
+        return match local
         {
-            self.position = Vector::sub(&self.position, &Vector::scale(&right, MOVEMENT_SPEED * delta));
-            self.moved = true;
-        }
+            Some(local) =>
+            {
+                let position = Point::from(local.origin).transform(&self.view_inverse);
+                let direction = Direction::from(local.direction).transform(&self.view_inverse).normalize();
+
+                Ray::new(position.into(), direction.into())
+            }
+
+            None => Ray::new(self.position, Vector::zero()),
+        };
+    }
+
+    pub fn recompute_rays(&mut self)
+    {
+        let (eye_width, eye_height) = self.eye_size();
+        let layout = self.stereo.map(|s| s.layout);
+
+        self.rays.resize(self.width * self.height, Ray::zero());
 
-        else if ctx.input(|i| i.key_pressed(egui::Key::D))
+        for y in 0..self.height
         {
-            self.position = Vector::add(&self.position, &Vector::scale(&right, MOVEMENT_SPEED * delta));
-            self.moved = true;
+            for x in 0..self.width
+            {
+                // an odd frame size leaves a spare column or row, which repeats the last one of the eye
+                let local = match layout
+                {
+                    Some(StereoLayout::SideBySide) if x < eye_width => self.eye_ray(x, y, Some(Eye::Left)),
+                    Some(StereoLayout::SideBySide) => self.eye_ray(usize::min(x - eye_width, eye_width - 1), y, Some(Eye::Right)),
+                    Some(StereoLayout::OverUnder) if y < eye_height => self.eye_ray(x, y, Some(Eye::Left)),
+                    Some(StereoLayout::OverUnder) => self.eye_ray(x, usize::min(y - eye_height, eye_height - 1), Some(Eye::Right)),
+                    Some(StereoLayout::Anaglyph) => self.eye_ray(x, y, Some(Eye::Left)),
+                    None => self.eye_ray(x, y, None),
+                };
+
+                self.rays[y * self.width + x] = self.to_world(local);
+            }
         }
 
-        if ctx.input(|i| i.key_pressed(egui::Key::Q))
+        if layout != Some(StereoLayout::Anaglyph)
         {
-            self.position = Vector::add(&self.position, &Vector::scale(&up, MOVEMENT_SPEED * delta));
-            self.moved = true;
+            self.partner_rays.clear();
+            return;
         }
 
-        else if ctx.input(|i| i.key_pressed(egui::Key::E))
+        self.partner_rays.resize(self.width * self.height, Ray::zero());
+
+        for y in 0..self.height
         {
-            self.position = Vector::sub(&self.position, &Vector::scale(&up, MOVEMENT_SPEED * delta));
-            self.moved = true;
+            for x in 0..self.width
+            {
+                let local = self.eye_ray(x, y, Some(Eye::Right));
+                self.partner_rays[y * self.width + x] = self.to_world(local);
+            }
         }
+    }
 
-        if ctx.input(|i| i.key_down(egui::Key::ArrowLeft))
+    // updates the pose without recomputing the view, so a batch of commands only rebuilds the matrices and rays once
+    fn apply(&mut self, command: &CameraCommand)
+    {
+        match *command
         {
-            self.yaw -= ROTATION_SPEED * delta;
-            self.moved = true;
+            CameraCommand::Move{ forward, right, up } =>
+            {
+                let world_up = Vector::new(0.0, 1.0, 0.0, 0.0);
+
+                // looking straight up or down leaves no horizontal component, so go by the top of the screen instead
+                let mut heading = self.direction;
+
+                if heading.x() * heading.x() + heading.z() * heading.z() < 1e-8
+                {
+                    heading = Quaternion::rotate(&self.orientation, &Vector::new(0.0, -self.direction.y(), 0.0, 0.0));
+                }
+
+                let dir = Vector::new(heading.x(), 0.0, heading.z(), 0.0);
+                let horizontal = Vector::normalize(&dir);
+                let side = Vector::cross(&world_up, &horizontal);
+
+                self.position += horizontal * forward;
+                self.position += side * right;
+                self.position += world_up * up;
+            }
+
+            CameraCommand::Turn{ yaw, pitch } =>
+            {
+                // pitch about the camera's own right axis and yaw about the world up, so the horizon stays level
+                let pitch = Quaternion::axis_angle(&Vector::new(1.0, 0.0, 0.0, 0.0), pitch);
+                let yaw = Quaternion::axis_angle(&Vector::new(0.0, 1.0, 0.0, 0.0), yaw);
+
+                let turned = Quaternion::mul(&Quaternion::mul(&pitch, &self.orientation), &yaw);
+                self.orientation = Quaternion::normalize(&turned);
+            }
+
+            CameraCommand::Pan{ right, up } =>
+            {
+                // along the screen axes, which stay defined when looking straight up or down
+                let side = Quaternion::rotate(&self.orientation, &Vector::new(1.0, 0.0, 0.0, 0.0));
+                let upward = Quaternion::rotate(&self.orientation, &Vector::new(0.0, 1.0, 0.0, 0.0));
+
+                // the pan rate grows with distance so the target tracks the pointer
+                let offset = side * (right * self.distance) + upward * (up * self.distance);
+                self.target += offset;
+            }
+
+            CameraCommand::Dolly{ amount } =>
+            {
+                if self.controller == Controller::Orbit
+                {
+                    self.distance = f32::max(self.distance * f32::exp(amount), self.near);
+                }
+            }
+
+            CameraCommand::SetPose{ position, yaw, pitch } =>
+            {
+                self.position = position;
+                self.orientation = Self::compose(yaw, pitch, self.roll());
+
+                // keep the orbit centered in front of the new pose
+                self.recompute_direction();
+                self.target = self.position + self.direction * self.distance;
+            }
+
+            CameraCommand::LookAt{ target } => self.aim(target),
+            CameraCommand::SetFov{ fov } => self.fov = fov,
+            CameraCommand::SetController{ controller } => self.set_controller(controller),
+
+            CameraCommand::ToggleController =>
+            {
+                let controller = match self.controller
+                {
+                    Controller::FreeFly => Controller::Orbit,
+                    Controller::Orbit => Controller::FreeFly,
+                };
+
+                self.set_controller(controller);
+            }
+
+            CameraCommand::Frame{ center, radius } => self.fit(center, radius),
         }
 
-        else if ctx.input(|i| i.key_down(egui::Key::ArrowRight))
+        // the direction and orbit position are cheap, and later commands in the batch move relative to them
+        self.recompute_direction();
+
+        if self.controller == Controller::Orbit
         {
-            self.yaw += ROTATION_SPEED * delta;
-            self.moved = true;
+            self.position = self.target - self.direction * self.distance;
         }
 
-        if ctx.input(|i| i.key_down(egui::Key::ArrowUp))
+        self.moved = true;
+    }
+
+    pub fn update(&mut self, commands: &[CameraCommand])
+    {
+        if commands.is_empty()
         {
-            self.pitch += ROTATION_SPEED * delta;
-            self.moved = true;
+            return;
         }
 
-        else if ctx.input(|i| i.key_down(egui::Key::ArrowDown))
+        for command in commands
         {
-            self.pitch -= ROTATION_SPEED * delta;
-            self.moved = true;
+            self.apply(command);
         }
 
-        // handles mouse interaction to provide click and pan functionality
-        let mouse = ctx.input(|i| i.pointer.press_origin());
-        if mouse.is_some()
+        // cheap next to the rays, so not worth tracking whether the fov changed
+        self.recompute_projection();
+        self.recompute_view();
+        self.recompute_rays();
+    }
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+
+    const TOLERANCE: f32 = 1e-4;
+
+    // a handful of rays is enough, the tests only look at the pose
+    fn camera() -> Camera
+    {
+        return Camera::new(90.0, 0.1, 1000.0, 8, 8);
+    }
+
+    fn assert_close(a: &Vector, b: &Vector)
+    {
+        let difference = Vector::length(&(*a - *b));
+        assert!(difference < TOLERANCE, "{:?} against {:?}", a, b);
+    }
+
+    #[test]
+    fn move_steps_by_the_commanded_distance()
+    {
+        let mut camera = camera();
+        let start = camera.position();
+
+        camera.update(&[CameraCommand::Move{ forward: 2.0, right: 0.0, up: 0.0 }]);
+        assert!(f32::abs(Vector::length(&(camera.position() - start)) - 2.0) < TOLERANCE);
+        assert!(f32::abs(camera.position().y() - start.y()) < TOLERANCE);
+        assert!(Vector::dot(&(camera.position() - start), &camera.direction()) > 0.0);
+
+        camera.update(&[CameraCommand::Move{ forward: -2.0, right: 0.0, up: 1.5 }]);
+        assert_close(&camera.position(), &Vector::new(start.x(), start.y() + 1.5, start.z(), 0.0));
+        assert!(camera.moved);
+    }
+
+    #[test]
+    fn move_follows_the_turned_heading()
+    {
+        let mut camera = camera();
+        camera.update(&[CameraCommand::Turn{ yaw: 0.5, pitch: 0.0 }]);
+
+        camera.update(&[CameraCommand::Move{ forward: 1.0, right: 0.0, up: 0.0 }]);
+        assert_close(&camera.position(), &Vector::new(f32::sin(0.5), 0.0, f32::cos(0.5), 0.0));
+
+        // right is the heading turned a quarter further toward +x
+        camera.update(&[CameraCommand::Move{ forward: 0.0, right: 1.0, up: 0.0 }]);
+        assert_close(&camera.position(), &Vector::new(f32::sin(0.5) + f32::cos(0.5), 0.0, f32::cos(0.5) - f32::sin(0.5), 0.0));
+    }
+
+    #[test]
+    fn turn_rotates_the_view_direction()
+    {
+        let mut camera = camera();
+        camera.update(&[CameraCommand::Turn{ yaw: 0.5, pitch: 0.0 }]);
+
+        assert_close(&camera.direction(), &Vector::new(f32::sin(0.5), 0.0, f32::cos(0.5), 0.0));
+        assert_close(&camera.position(), &Vector::zero());
+    }
+
+    fn assert_rotation(a: &Quaternion, b: &Quaternion)
+    {
+        // q and -q are the same rotation
+        assert!(f32::abs(Quaternion::dot(a, b)) > 1.0 - TOLERANCE, "{:?} against {:?}", a, b);
+    }
+
+    #[test]
+    fn orientation_round_trips_at_and_past_vertical()
+    {
+        let mut camera = camera();
+
+        for pitch in [0.0, 1.2, 0.5 * PI, -0.5 * PI, 0.5 * PI + 0.4, PI - 0.1, -PI + 0.3]
         {
-            let mouse_pos = mouse.unwrap();
-            let delta_x = mouse_pos.x - ctx.screen_rect().center().x;
-            let delta_y = mouse_pos.y - ctx.screen_rect().center().y;
+            let orientation = Camera::compose(0.7, pitch, -0.3);
+            camera.set_orientation(&orientation);
+            assert_rotation(&camera.orientation(), &orientation);
+
+            let position = Vector::new(1.0, -2.0, 3.0, 1.0);
+            camera.set_transform(position, &orientation);
+            assert_rotation(&camera.orientation(), &orientation);
+            assert_close(&camera.position(), &position);
+
+            // the view looks along the rotated z with the rotated y on top of the screen
+            let forward = Quaternion::rotate(&orientation, &Vector::new(0.0, 0.0, 1.0, 0.0));
+            assert_close(&camera.direction(), &forward);
 
-            self.yaw = delta_x * ROTATION_SPEED * delta;
-            self.pitch = delta_y * ROTATION_SPEED * delta;
+            let center = camera.rays()[4 * 8 + 4].direction;
+            assert!(Vector::dot(&center, &forward) > 0.99);
+        }
+    }
+
+    #[test]
+    fn angles_rebuild_the_orientation()
+    {
+        let mut camera = camera();
+        let orientation = Camera::compose(2.5, -0.8, 0.4);
+        camera.set_orientation(&orientation);
+
+        assert!(f32::abs(camera.yaw() - 2.5) < TOLERANCE);
+        assert!(f32::abs(camera.pitch() + 0.8) < TOLERANCE);
+        assert!(f32::abs(camera.roll() - 0.4) < TOLERANCE);
+
+        camera.set_roll(0.0);
+        assert_rotation(&camera.orientation(), &Camera::compose(2.5, -0.8, 0.0));
+    }
+
+    #[test]
+    fn turn_passes_straight_down_without_clamping()
+    {
+        let mut camera = camera();
+
+        camera.update(&[CameraCommand::Turn{ yaw: 0.0, pitch: 0.5 * PI }]);
+        assert_close(&camera.direction(), &Vector::new(0.0, -1.0, 0.0, 0.0));
+
+        // moving still heads towards the top of the screen rather than going nowhere
+        let start = camera.position();
+        camera.update(&[CameraCommand::Move{ forward: 1.0, right: 0.0, up: 0.0 }]);
+        assert!(f32::abs(Vector::length(&(camera.position() - start)) - 1.0) < TOLERANCE);
 
-            self.moved = true;
+        camera.update(&[CameraCommand::Turn{ yaw: 0.0, pitch: 0.25 * PI }]);
+        assert_close(&camera.direction(), &Vector::new(0.0, -f32::sin(0.25 * PI), -f32::cos(0.25 * PI), 0.0));
+        assert!(camera.rays().iter().all(|ray| Vector::length2(&ray.direction).is_finite()));
+    }
+
+    #[test]
+    fn orbit_keeps_the_distance_to_the_target()
+    {
+        let mut camera = camera();
+        camera.update(&[CameraCommand::SetController{ controller: Controller::Orbit }]);
+
+        let target = camera.target();
+        let distance = Vector::length(&(target - camera.position()));
+
+        for _ in 0..10
+        {
+            camera.update(&[CameraCommand::Turn{ yaw: 0.3, pitch: 0.1 }]);
+
+            assert_close(&camera.target(), &target);
+            assert!(f32::abs(Vector::length(&(target - camera.position())) - distance) < TOLERANCE);
+            assert_close(&(camera.position() + camera.direction() * distance), &target);
         }
+    }
+
+    #[test]
+    fn dolly_only_applies_in_orbit()
+    {
+        let mut camera = camera();
+        let start = camera.position();
+
+        camera.update(&[CameraCommand::Dolly{ amount: 1.0 }]);
+        assert_close(&camera.position(), &start);
 
-        const PI: f32 = 3.14159265358979323846;
+        camera.update(&[CameraCommand::SetController{ controller: Controller::Orbit }, CameraCommand::Dolly{ amount: f32::ln(2.0) }]);
 
-        self.pitch = f32::clamp(self.pitch, -PI / 4.0, PI / 4.0);
+        let distance = Vector::length(&(camera.target() - camera.position()));
+        assert!(f32::abs(distance - 10.0) < TOLERANCE);
+    }
+
+    #[test]
+    fn toggle_follows_the_camera_mode()
+    {
+        let mut camera = camera();
+
+        // a mode set from elsewhere must not leave the toggle out of step
+        camera.update(&[CameraCommand::SetController{ controller: Controller::Orbit }]);
+        camera.update(&[CameraCommand::ToggleController]);
+        assert_eq!(camera.controller(), Controller::FreeFly);
+
+        camera.update(&[CameraCommand::ToggleController]);
+        assert_eq!(camera.controller(), Controller::Orbit);
+    }
+
+    #[test]
+    fn frame_fits_the_sphere_in_view()
+    {
+        let mut camera = camera();
+        let center = Vector::new(1.0, 2.0, 3.0, 0.0);
+
+        camera.update(&[CameraCommand::Turn{ yaw: 0.4, pitch: 0.0 }, CameraCommand::Frame{ center: center, radius: 2.0 }]);
+
+        // 90 degrees across, so the sphere touches the edges at radius / sin 45
+        let distance = 2.0 / f32::sin(0.25 * PI);
+        assert_close(&(camera.position() + camera.direction() * distance), &center);
+        assert_close(&camera.direction(), &Vector::new(f32::sin(0.4), 0.0, f32::cos(0.4), 0.0));
+    }
 
-        let cos_pitch = f32::cos(self.pitch);
-        let sin_pitch = f32::sin(self.pitch);
+    #[test]
+    fn look_at_points_at_the_target()
+    {
+        let mut camera = camera();
+        let target = Vector::new(3.0, -1.0, 2.0, 0.0);
+
+        camera.update(&[CameraCommand::LookAt{ target: target }]);
+
+        let expected = Vector::normalize(&(target - camera.position()));
+        assert_close(&camera.direction(), &Vector::new(expected.x(), expected.y(), expected.z(), 0.0));
+    }
 
-        let cos_yaw = f32::cos(self.yaw);
-        let sin_yaw = f32::sin(self.yaw);
+    #[test]
+    fn batch_matches_one_command_at_a_time()
+    {
+        let commands =
+        [
+            CameraCommand::Move{ forward: 1.0, right: 0.5, up: 0.25 },
+            CameraCommand::Turn{ yaw: 0.2, pitch: -0.1 },
+            CameraCommand::SetFov{ fov: 60.0 },
+            CameraCommand::LookAt{ target: Vector::new(0.0, 1.0, 6.0, 0.0) },
+            CameraCommand::Move{ forward: -0.5, right: 0.0, up: 0.0 },
+        ];
 
-        self.direction = Vector::new
-        (
-            cos_pitch * sin_yaw,
-            -sin_pitch,
-            cos_pitch * cos_yaw,
-            0.0,
-        );
+        let mut batched = camera();
+        batched.update(&commands);
 
-        self.direction = Vector::normalize(&self.direction);
+        let mut sequential = camera();
 
-        if self.moved
+        for command in commands.iter()
         {
-            self.recompute_view();
-            self.recompute_rays();
+            sequential.update(std::slice::from_ref(command));
         }
 
+        assert_close(&batched.position(), &sequential.position());
+        assert_close(&batched.direction(), &sequential.direction());
+        assert_eq!(batched.fov(), 60.0);
+
+        for row in 0..4
+        {
+            for column in 0..4
+            {
+                assert_eq!(batched.projection().get(row, column), sequential.projection().get(row, column));
+            }
+        }
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,228 @@
+// (c) 2025 Connor J. Link. All Rights Reserved.
+// Luma - color.rs
+
+use crate::vector::*;
+
+// the conversion matrices are written the way they are published, for column vectors, so out = M * in
+
+// linear rec.709 / srgb primaries with a d65 white point to cie xyz
+const REC709_TO_XYZ: [[f32; 3]; 3] =
+[
+    [0.4124564, 0.3575761, 0.1804375],
+    [0.2126729, 0.7151522, 0.0721750],
+    [0.0193339, 0.1191920, 0.9503041],
+];
+
+const XYZ_TO_REC709: [[f32; 3]; 3] =
+[
+    [3.2404542, -1.5371385, -0.4985314],
+    [-0.9692660, 1.8760108, 0.0415560],
+    [0.0556434, -0.2040259, 1.0572252],
+];
+
+// aces ap1 primaries with the aces (~d60) white point to cie xyz
+const ACESCG_TO_XYZ: [[f32; 3]; 3] =
+[
+    [0.6624542, 0.1340042, 0.1561877],
+    [0.2722287, 0.6740818, 0.0536895],
+    [-0.0055746, 0.0040607, 1.0103391],
+];
+
+const XYZ_TO_ACESCG: [[f32; 3]; 3] =
+[
+    [1.6410234, -0.3248033, -0.2364247],
+    [-0.6636629, 1.6153316, 0.0167563],
+    [0.0117219, -0.0082844, 0.9883949],
+];
+
+// direct conversions between the working spaces, including a bradford adaptation between the two white points so
+// that white stays white. going through xyz alone would tint neutrals slightly
+const REC709_TO_ACESCG: [[f32; 3]; 3] =
+[
+    [0.6131324, 0.3395381, 0.0473296],
+    [0.0701934, 0.9163539, 0.0134527],
+    [0.0206155, 0.1095697, 0.8698148],
+];
+
+const ACESCG_TO_REC709: [[f32; 3]; 3] =
+[
+    [1.7050510, -0.6217921, -0.0832590],
+    [-0.1302564, 1.1408047, -0.0105483],
+    [-0.0240034, -0.1289690, 1.1529724],
+];
+
+// the linear space lighting is computed in. scene colours are authored as linear rec.709 and converted on the way in
+#[derive(Clone)]
+#[derive(Copy)]
+#[derive(Debug)]
+#[derive(PartialEq)]
+pub enum ColorSpace
+{
+    LinearSrgb,
+    AcesCg,
+}
+
+impl ColorSpace
+{
+    pub fn name(&self) -> &'static str
+    {
+        return match self
+        {
+            ColorSpace::LinearSrgb => "Linear sRGB",
+            ColorSpace::AcesCg => "ACEScg",
+        };
+    }
+
+    pub fn to_xyz(&self, color: &Vector) -> Vector
+    {
+        return match self
+        {
+            ColorSpace::LinearSrgb => mul3(&REC709_TO_XYZ, color),
+            ColorSpace::AcesCg => mul3(&ACESCG_TO_XYZ, color),
+        };
+    }
+
+    pub fn from_xyz(&self, xyz: &Vector) -> Vector
+    {
+        return match self
+        {
+            ColorSpace::LinearSrgb => mul3(&XYZ_TO_REC709, xyz),
+            ColorSpace::AcesCg => mul3(&XYZ_TO_ACESCG, xyz),
+        };
+    }
+
+    // alpha passes through untouched
+    pub fn convert(color: &Vector, from: ColorSpace, to: ColorSpace) -> Vector
+    {
+        return match (from, to)
+        {
+            (ColorSpace::LinearSrgb, ColorSpace::AcesCg) => mul3(&REC709_TO_ACESCG, color),
+            (ColorSpace::AcesCg, ColorSpace::LinearSrgb) => mul3(&ACESCG_TO_REC709, color),
+            _ => *color,
+        };
+    }
+}
+
+// what the presentation surface expects to be handed. an srgb surface format encodes in hardware as it stores, so
+// writing encoded values to it would encode twice, while a plain unorm surface stores exactly what it is given
+#[derive(Clone)]
+#[derive(Copy)]
+#[derive(Debug)]
+#[derive(PartialEq)]
+pub enum Encoding
+{
+    Linear,
+    Srgb,
+}
+
+impl Encoding
+{
+    pub fn for_surface(is_srgb: bool) -> Encoding
+    {
+        return if is_srgb { Encoding::Linear } else { Encoding::Srgb };
+    }
+
+    pub fn apply(&self, color: &Vector) -> Vector
+    {
+        return match self
+        {
+            Encoding::Linear => *color,
+            Encoding::Srgb => encode_srgb(color),
+        };
+    }
+}
+
+// scene-referred working space to display-referred values for the surface: exposure, the tonemap curve on linear
+// rec.709, then the transfer function if the surface doesn't apply one itself
+#[derive(Clone)]
+#[derive(Copy)]
+#[derive(Debug)]
+pub struct OutputTransform
+{
+    pub working: ColorSpace,
+    pub exposure: f32,
+    pub tonemap: bool,
+    pub encoding: Encoding,
+}
+
+impl OutputTransform
+{
+    pub fn new() -> OutputTransform
+    {
+        return OutputTransform
+        {
+            working: ColorSpace::LinearSrgb,
+            exposure: 1.0,
+            tonemap: true,
+            encoding: Encoding::Srgb,
+        };
+    }
+
+    pub fn apply(&self, color: &Vector) -> Vector
+    {
+        let display = ColorSpace::convert(color, self.working, ColorSpace::LinearSrgb);
+
+        let mapped = |channel: f32|
+        {
+            let exposed = f32::max(channel * self.exposure, 0.0);
+            return if self.tonemap { 1.0 - f32::exp(-exposed) } else { f32::min(exposed, 1.0) };
+        };
+
+        let mapped = Vector::new(mapped(display.x()), mapped(display.y()), mapped(display.z()), color.w());
+        return self.encoding.apply(&mapped);
+    }
+
+    // the same transform packed for a shader: exposure, tonemap and encode flags, then an unused lane
+    pub fn uniform(&self) -> [f32; 4]
+    {
+        let tonemap = if self.tonemap { 1.0 } else { 0.0 };
+        let encode = if self.encoding == Encoding::Srgb { 1.0 } else { 0.0 };
+
+        return [self.exposure, tonemap, encode, 0.0];
+    }
+}
+
+// the piecewise srgb transfer function (iec 61966-2-1), not a plain 2.2 gamma, which is visibly off near black
+pub fn srgb_to_linear(value: f32) -> f32
+{
+    if value <= 0.04045
+    {
+        return value / 12.92;
+    }
+
+    return f32::powf((value + 0.055) / 1.055, 2.4);
+}
+
+pub fn linear_to_srgb(value: f32) -> f32
+{
+    if value <= 0.0031308
+    {
+        return value * 12.92;
+    }
+
+    return 1.055 * f32::powf(value, 1.0 / 2.4) - 0.055;
+}
+
+// for 8-bit textures and vertex colours picked on screen, which are stored encoded. alpha is always linear
+pub fn decode_srgb(color: &Vector) -> Vector
+{
+    return Vector::new(srgb_to_linear(color.x()), srgb_to_linear(color.y()), srgb_to_linear(color.z()), color.w());
+}
+
+pub fn encode_srgb(color: &Vector) -> Vector
+{
+    return Vector::new(linear_to_srgb(color.x()), linear_to_srgb(color.y()), linear_to_srgb(color.z()), color.w());
+}
+
+fn mul3(m: &[[f32; 3]; 3], color: &Vector) -> Vector
+{
+    let (r, g, b) = (color.x(), color.y(), color.z());
+
+    return Vector::new
+    (
+        m[0][0] * r + m[0][1] * g + m[0][2] * b,
+        m[1][0] * r + m[1][1] * g + m[1][2] * b,
+        m[2][0] * r + m[2][1] * g + m[2][2] * b,
+        color.w(),
+    );
+}
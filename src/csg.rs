@@ -0,0 +1,260 @@
+// (c) 2025 Connor J. Link. All Rights Reserved.
+// Luma - csg.rs
+
+use std::rc::Rc;
+
+use crate::vector::*;
+use crate::ray::*;
+use crate::shape::*;
+use crate::bounds::*;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Operation
+{
+    Union,
+    Intersection,
+    Difference,
+}
+
+// boolean combination of two solids evaluated over their ray intervals
+pub struct Csg
+{
+    operation: Operation,
+    left: Rc<dyn Shape>,
+    right: Rc<dyn Shape>,
+}
+
+struct Event
+{
+    crossing: Crossing,
+    left: bool,
+    entering: bool,
+}
+
+impl Csg
+{
+    pub fn new(operation: Operation, left: Rc<dyn Shape>, right: Rc<dyn Shape>) -> Csg
+    {
+        return Csg{ operation: operation, left: left, right: right };
+    }
+
+    pub fn union(left: Rc<dyn Shape>, right: Rc<dyn Shape>) -> Csg
+    {
+        return Csg::new(Operation::Union, left, right);
+    }
+
+    pub fn intersection(left: Rc<dyn Shape>, right: Rc<dyn Shape>) -> Csg
+    {
+        return Csg::new(Operation::Intersection, left, right);
+    }
+
+    pub fn difference(left: Rc<dyn Shape>, right: Rc<dyn Shape>) -> Csg
+    {
+        return Csg::new(Operation::Difference, left, right);
+    }
+
+    pub fn operation(&self) -> Operation
+    {
+        return self.operation;
+    }
+
+    fn inside(&self, left: bool, right: bool) -> bool
+    {
+        return match self.operation
+        {
+            Operation::Union => left || right,
+            Operation::Intersection => left && right,
+            Operation::Difference => left && !right,
+        };
+    }
+
+    fn events(spans: &[Span], left: bool, events: &mut Vec<Event>)
+    {
+        for span in spans
+        {
+            events.push(Event{ crossing: span.enter, left: left, entering: true });
+            events.push(Event{ crossing: span.exit, left: left, entering: false });
+        }
+    }
+
+    fn combine(&self, left: &[Span], right: &[Span], spans: &mut Vec<Span>)
+    {
+        let mut events = Vec::with_capacity(2 * (left.len() + right.len()));
+
+        Self::events(left, true, &mut events);
+        Self::events(right, false, &mut events);
+
+        events.sort_by(|a, b| a.crossing.distance.total_cmp(&b.crossing.distance));
+
+        let mut in_left = false;
+        let mut in_right = false;
+        let mut inside = false;
+
+        let mut enter = None;
+
+        for event in &events
+        {
+            if event.left
+            {
+                in_left = event.entering;
+            }
+
+            else
+            {
+                in_right = event.entering;
+            }
+
+            let now = self.inside(in_left, in_right);
+
+            if now == inside
+            {
+                continue;
+            }
+
+            let mut crossing = event.crossing;
+
+            // surfaces carved out by the right operand face into the hole they leave behind
+            if self.operation == Operation::Difference && !event.left
+            {
+                crossing.normal = Vector::scale(&crossing.normal, -1.0);
+            }
+
+            if now
+            {
+                enter = Some(crossing);
+            }
+
+            else if let Some(start) = enter.take()
+            {
+                spans.push(Span::new(start, crossing));
+            }
+
+            inside = now;
+        }
+    }
+}
+
+impl Shape for Csg
+{
+    fn intersect(&self, ray: &Ray) -> Option<Hit>
+    {
+        let bounds = self.bounds();
+
+        if bounds.is_finite() && Aabb::intersect(&bounds, ray).is_none()
+        {
+            return None;
+        }
+
+        let mut spans = Vec::new();
+        self.spans(ray, &mut spans);
+
+        return first(ray, &spans);
+    }
+
+    fn bounds(&self) -> Aabb
+    {
+        let left = self.left.bounds();
+        let right = self.right.bounds();
+
+        return match self.operation
+        {
+            Operation::Union => Aabb::union(&left, &right),
+            Operation::Intersection => Aabb::intersection(&left, &right),
+            Operation::Difference => left,
+        };
+    }
+
+    fn spans(&self, ray: &Ray, spans: &mut Vec<Span>)
+    {
+        let mut left = Vec::new();
+        self.left.spans(ray, &mut left);
+
+        if left.is_empty() && self.operation != Operation::Union
+        {
+            return;
+        }
+
+        let mut right = Vec::new();
+        self.right.spans(ray, &mut right);
+
+        self.combine(&left, &right, spans);
+    }
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+    use crate::matrix::*;
+    use crate::primitive::*;
+    use crate::instance::*;
+
+    const TOLERANCE: f32 = 1e-4;
+
+    fn assert_close(a: &Vector, b: &Vector)
+    {
+        let difference = Vector::length(&Vector::sub(a, b));
+        assert!(difference < TOLERANCE, "{:?} against {:?}", a, b);
+    }
+
+    // a two unit cube with a half unit sphere scooped out of the middle of its -z face
+    fn dented() -> Csg
+    {
+        let block = Rc::new(Cuboid::new(-Vector::broadcast(1.0), Vector::broadcast(1.0)));
+        let scoop = Rc::new(Sphere::new(Vector::new(0.0, 0.0, -1.0, 1.0), 0.5));
+
+        return Csg::difference(block, scoop);
+    }
+
+    fn toward_z(x: f32) -> Ray
+    {
+        return Ray::new(Vector::new(x, 0.0, -5.0, 1.0), Vector::new(0.0, 0.0, 1.0, 0.0));
+    }
+
+    #[test]
+    fn difference_hits_the_floor_of_the_dent()
+    {
+        let hit = dented().intersect(&toward_z(0.3)).expect("the ray should hit the dent");
+
+        // the sphere crosses x = 0.3 at z = -1 + sqrt(0.25 - 0.09)
+        assert!(f32::abs(hit.distance - 4.4) < TOLERANCE, "{}", hit.distance);
+        assert_close(&Vector::new(hit.position.x(), hit.position.y(), hit.position.z(), 0.0), &Vector::new(0.3, 0.0, -0.6, 0.0));
+
+        // the sphere's outward normal is flipped to face out of the dent, back toward the ray
+        assert_close(&hit.normal, &Vector::new(-0.6, 0.0, -0.8, 0.0));
+        assert!(Vector::dot(&hit.normal, &toward_z(0.3).direction) < 0.0);
+    }
+
+    #[test]
+    fn difference_keeps_the_left_normal_outside_the_dent()
+    {
+        let hit = dented().intersect(&toward_z(0.8)).expect("the ray should hit the face");
+
+        assert!(f32::abs(hit.distance - 4.0) < TOLERANCE, "{}", hit.distance);
+        assert_close(&hit.normal, &Vector::new(0.0, 0.0, -1.0, 0.0));
+    }
+
+    #[test]
+    fn difference_hits_through_an_instance()
+    {
+        // the same dent placed the way the scene places objects
+        let translation = Matrix::translation(&Vector::new(2.0, 0.0, 3.0, 0.0));
+        let instance = Instance::new(Rc::new(dented()), translation);
+
+        let hit = instance.intersect(&toward_z(2.3)).expect("the ray should hit the dent");
+
+        assert!(f32::abs(hit.distance - 7.4) < TOLERANCE, "{}", hit.distance);
+        assert_close(&Vector::new(hit.position.x(), hit.position.y(), hit.position.z(), 0.0), &Vector::new(2.3, 0.0, 2.4, 0.0));
+        assert_close(&Vector::normalize(&hit.normal), &Vector::new(-0.6, 0.0, -0.8, 0.0));
+    }
+
+    #[test]
+    fn difference_enters_where_the_ray_leaves_the_scoop()
+    {
+        // straight through the middle the solid begins where the ray leaves the sphere, on its flipped far side
+        let hit = dented().intersect(&toward_z(0.0)).expect("the ray should hit the dent");
+
+        assert!(f32::abs(hit.distance - 4.5) < TOLERANCE, "{}", hit.distance);
+        assert_close(&hit.normal, &Vector::new(0.0, 0.0, -1.0, 0.0));
+    }
+}
@@ -0,0 +1,28 @@
+// (c) 2025 Connor J. Link. All Rights Reserved.
+// Luma - image.rs
+
+use std::fs;
+use std::io::Write;
+use std::path::Path;
+
+// writes an 8-bit rgba bitmap as a binary portable pixmap, dropping alpha
+pub fn write_ppm(path: &str, width: usize, height: usize, rgba: &[u8]) -> std::io::Result<()>
+{
+    if let Some(parent) = Path::new(path).parent()
+    {
+        if !parent.as_os_str().is_empty()
+        {
+            fs::create_dir_all(parent)?;
+        }
+    }
+
+    let mut data = Vec::with_capacity(width * height * 3 + 32);
+    write!(data, "P6\n{} {}\n255\n", width, height)?;
+
+    for pixel in rgba.chunks_exact(4).take(width * height)
+    {
+        data.extend_from_slice(&pixel[0..3]);
+    }
+
+    return fs::write(path, data);
+}
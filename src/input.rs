@@ -0,0 +1,231 @@
+// (c) 2025 Connor J. Link. All Rights Reserved.
+// Luma - input.rs
+
+use crate::vector::*;
+use crate::camera::*;
+
+// device-independent camera actions, with motion already scaled to world units and radians
+#[derive(Clone, Copy, Debug)]
+pub enum CameraCommand
+{
+    // translation along the camera's horizontal forward, right and world up axes
+    Move{ forward: f32, right: f32, up: f32 },
+
+    // change of heading and elevation, around the eye in free fly or around the target in orbit
+    Turn{ yaw: f32, pitch: f32 },
+
+    // shifts the orbit target within the view plane by a fraction of the orbit distance
+    Pan{ right: f32, up: f32 },
+
+    // logarithmic change of the orbit distance, ignored in free fly
+    Dolly{ amount: f32 },
+
+    SetPose{ position: Vector, yaw: f32, pitch: f32 },
+    LookAt{ target: Vector },
+    SetFov{ fov: f32 },
+    SetController{ controller: Controller },
+
+    // switches between free fly and orbit, whichever the camera is in now
+    ToggleController,
+    Frame{ center: Vector, radius: f32 },
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct Controls
+{
+    pub movement_speed: f32,
+    pub rotation_speed: f32,
+
+    // radians per pixel of pointer motion
+    pub look_sensitivity: f32,
+
+    // fraction of the orbit distance per pixel of pointer motion
+    pub pan_sensitivity: f32,
+
+    // exponential zoom per point of scrolling
+    pub dolly_sensitivity: f32,
+}
+
+impl Controls
+{
+    pub fn new() -> Controls
+    {
+        return Controls{ movement_speed: 0.75, rotation_speed: 1.0, look_sensitivity: 0.005, pan_sensitivity: 0.002, dolly_sensitivity: 0.01 };
+    }
+}
+
+// the keys the camera can be bound to, named independently of any windowing library
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Key
+{
+    W, A, S, D, Q, E, R, F,
+    Space,
+    Tab,
+    ArrowUp,
+    ArrowDown,
+    ArrowLeft,
+    ArrowRight,
+}
+
+impl Key
+{
+    pub const ALL: [Key; 14] =
+    [
+        Key::W, Key::A, Key::S, Key::D, Key::Q, Key::E, Key::R, Key::F,
+        Key::Space, Key::Tab, Key::ArrowUp, Key::ArrowDown, Key::ArrowLeft, Key::ArrowRight,
+    ];
+}
+
+// a snapshot of the keyboard and pointer for one frame, filled in by whichever windowing layer is driving the app
+#[derive(Clone, Debug)]
+pub struct InputState
+{
+    // keys down this frame, and the subset that went down since the last one
+    pub held: Vec<Key>,
+    pub pressed: Vec<Key>,
+
+    pub primary: bool,
+    pub middle: bool,
+
+    // pointer motion in pixels in x and y
+    pub motion: Vector,
+
+    // points scrolled, positive away from the user
+    pub scroll: f32,
+}
+
+impl InputState
+{
+    pub fn new() -> InputState
+    {
+        return InputState{ held: Vec::new(), pressed: Vec::new(), primary: false, middle: false, motion: Vector::zero(), scroll: 0.0 };
+    }
+
+    pub fn is_held(&self, key: Key) -> bool
+    {
+        return self.held.contains(&key);
+    }
+
+    pub fn is_pressed(&self, key: Key) -> bool
+    {
+        return self.pressed.contains(&key);
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct Bindings
+{
+    pub forward: Key,
+    pub backward: Key,
+    pub left: Key,
+    pub right: Key,
+    pub up: Key,
+    pub down: Key,
+    pub toggle: Key,
+}
+
+impl Bindings
+{
+    pub fn new() -> Bindings
+    {
+        return Bindings
+        {
+            forward: Key::W,
+            backward: Key::S,
+            left: Key::A,
+            right: Key::D,
+            up: Key::Q,
+            down: Key::E,
+            toggle: Key::Tab,
+        };
+    }
+}
+
+// translates keyboard and pointer state into camera commands. the camera owns the controller mode, which is passed in
+// so the two can't disagree after something else changes it
+pub struct InputController
+{
+    pub controls: Controls,
+    pub bindings: Bindings,
+}
+
+impl InputController
+{
+    pub fn new() -> InputController
+    {
+        return InputController{ controls: Controls::new(), bindings: Bindings::new() };
+    }
+
+    // held keys drive continuous motion, scaled by the frame time so speed doesn't depend on the frame rate
+    fn axis(state: &InputState, positive: Key, negative: Key) -> f32
+    {
+        if state.is_held(positive)
+        {
+            return 1.0;
+        }
+
+        if state.is_held(negative)
+        {
+            return -1.0;
+        }
+
+        return 0.0;
+    }
+
+    pub fn poll(&mut self, state: &InputState, delta: f32, controller: Controller, commands: &mut Vec<CameraCommand>)
+    {
+        let controls = self.controls;
+        let bindings = self.bindings;
+
+        if state.is_pressed(bindings.toggle)
+        {
+            commands.push(CameraCommand::ToggleController);
+        }
+
+        let motion = state.motion;
+        let moving = motion.x() != 0.0 || motion.y() != 0.0;
+
+        // relative mouse look in free fly and arcball rotation in orbit share the primary drag
+        if state.primary && moving
+        {
+            commands.push(CameraCommand::Turn{ yaw: motion.x() * controls.look_sensitivity, pitch: motion.y() * controls.look_sensitivity });
+        }
+
+        let yaw = Self::axis(state, Key::ArrowRight, Key::ArrowLeft);
+        let pitch = Self::axis(state, Key::ArrowUp, Key::ArrowDown);
+
+        if yaw != 0.0 || pitch != 0.0
+        {
+            commands.push(CameraCommand::Turn{ yaw: yaw * controls.rotation_speed * delta, pitch: pitch * controls.rotation_speed * delta });
+        }
+
+        match controller
+        {
+            Controller::FreeFly =>
+            {
+                let forward = Self::axis(state, bindings.forward, bindings.backward);
+                let right = Self::axis(state, bindings.right, bindings.left);
+                let up = Self::axis(state, bindings.up, bindings.down);
+
+                if forward != 0.0 || right != 0.0 || up != 0.0
+                {
+                    let step = controls.movement_speed * delta;
+                    commands.push(CameraCommand::Move{ forward: forward * step, right: right * step, up: up * step });
+                }
+            }
+
+            Controller::Orbit =>
+            {
+                if state.middle && moving
+                {
+                    commands.push(CameraCommand::Pan{ right: -motion.x() * controls.pan_sensitivity, up: motion.y() * controls.pan_sensitivity });
+                }
+
+                if state.scroll != 0.0
+                {
+                    commands.push(CameraCommand::Dolly{ amount: -state.scroll * controls.dolly_sensitivity });
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,114 @@
+// (c) 2025 Connor J. Link. All Rights Reserved.
+// Luma - instance.rs
+
+use std::rc::Rc;
+
+use crate::matrix::*;
+use crate::vector::*;
+use crate::ray::*;
+use crate::shape::*;
+use crate::bounds::*;
+
+// places a shared shape in the world with its own object-to-world transform
+pub struct Instance
+{
+    shape: Rc<dyn Shape>,
+    transform: Matrix,
+
+    // none for a degenerate transform, such as a zero scale, which makes the instance invisible
+    inverse: Option<Matrix>,
+}
+
+impl Instance
+{
+    pub fn new(shape: Rc<dyn Shape>, transform: Matrix) -> Instance
+    {
+        // instance transforms are affine, and the affine inverse judges conditioning on the upper 3x3 alone, so a
+        // large translation doesn't count against it the way it does in the general inverse
+        let inverse = Matrix::affine_inverse(&transform);
+        return Instance{ shape: shape, transform: transform, inverse: inverse };
+    }
+
+    pub fn shape(&self) -> &Rc<dyn Shape>
+    {
+        return &self.shape;
+    }
+
+    pub fn transform(&self) -> &Matrix
+    {
+        return &self.transform;
+    }
+
+    pub fn inverse(&self) -> Option<&Matrix>
+    {
+        return self.inverse.as_ref();
+    }
+
+    pub fn is_degenerate(&self) -> bool
+    {
+        return self.inverse.is_none();
+    }
+
+    pub fn set_transform(&mut self, transform: Matrix)
+    {
+        self.inverse = Matrix::affine_inverse(&transform);
+        self.transform = transform;
+    }
+
+    pub fn to_object(&self, ray: &Ray) -> Option<Ray>
+    {
+        let inverse = self.inverse.as_ref()?;
+
+        // the direction is left unnormalized so distances agree between both spaces
+        let origin = Matrix::transform_point(inverse, &ray.origin);
+        let direction = Matrix::transform_direction(inverse, &ray.direction);
+
+        return Some(Ray::new(origin, direction));
+    }
+
+    pub fn to_world(&self, hit: &Hit) -> Option<Hit>
+    {
+        let inverse = self.inverse.as_ref()?;
+
+        // carrying the object-space point over keeps whatever accuracy the shape achieved for it
+        let position = Matrix::transform_point(&self.transform, &hit.position);
+        let error = Matrix::transform_error(&self.transform, &hit.position, &hit.error);
+        let normal = Vector::normalize(&Matrix::transform_normal(inverse, &hit.normal));
+
+        return Some(Hit{ position: position, normal: normal, uv: hit.uv, distance: hit.distance, exit: hit.exit, error: error });
+    }
+}
+
+impl Shape for Instance
+{
+    fn intersect(&self, ray: &Ray) -> Option<Hit>
+    {
+        let local = self.to_object(ray)?;
+        let hit = self.shape.intersect(&local)?;
+
+        return self.to_world(&hit);
+    }
+
+    fn bounds(&self) -> Aabb
+    {
+        return Aabb::transform(&self.shape.bounds(), &self.transform);
+    }
+
+    fn spans(&self, ray: &Ray, spans: &mut Vec<Span>)
+    {
+        let (Some(local), Some(inverse)) = (self.to_object(ray), self.inverse.as_ref()) else
+        {
+            return;
+        };
+
+        let start = spans.len();
+
+        self.shape.spans(&local, spans);
+
+        for span in &mut spans[start..]
+        {
+            span.enter.normal = Vector::normalize(&Matrix::transform_normal(inverse, &span.enter.normal));
+            span.exit.normal = Vector::normalize(&Matrix::transform_normal(inverse, &span.exit.normal));
+        }
+    }
+}
@@ -1,7 +1,10 @@
 // #![windows_subsystem = "windows"] // uncomment this to suppress terminal on windows
 
 mod vector;
+mod simd;
 mod geometry;
+mod color;
+mod world;
 
 #[cfg(target_arch = "wasm32")]
 use wasm_bindgen::prelude::*;
@@ -522,7 +525,7 @@ impl GPU
             .formats
             .iter()
             .copied()
-            .find(|f| !f.is_srgb()) // egui wants a non-srgb surface texture
+            .find(|f| !f.is_srgb()) // egui wants a non-srgb surface texture, so the scene shader encodes for itself
             .unwrap_or(surface_capabilities.formats[0]);
 
         let surface_config = wgpu::SurfaceConfiguration {
@@ -554,6 +557,13 @@ struct Scene {
     pub index_buffer: wgpu::Buffer,
     pub uniform: UniformBinding,
     pub pipeline: wgpu::RenderPipeline,
+
+    // the same output transform as the raytracer, with the encoding matched to the surface format
+    pub output: color::OutputTransform,
+
+    // world placement of the model and the eye, kept in double precision. only their difference reaches the gpu
+    pub position: world::WorldPosition,
+    pub eye: world::WorldPosition,
 }
 
 impl Scene {
@@ -576,12 +586,20 @@ impl Scene {
         );
         let uniform = UniformBinding::new(device);
         let pipeline = Self::create_pipeline(device, surface_format, &uniform);
+        let output = color::OutputTransform
+        {
+            encoding: color::Encoding::for_surface(surface_format.is_srgb()),
+            ..color::OutputTransform::new()
+        };
         Self {
             model: nalgebra_glm::Mat4::identity(),
             uniform,
             pipeline,
             vertex_buffer,
             index_buffer,
+            output,
+            position: world::WorldPosition::origin(),
+            eye: world::WorldPosition::new(0.0, 0.0, 3.0),
         }
     }
 
@@ -600,10 +618,15 @@ impl Scene {
     {
         let projection = nalgebra_glm::perspective_lh_zo(aspect_ratio, 80_f32.to_radians(), 0.1, 1000.0);
 
+        // camera-relative: the view sits at the origin and the model is moved by its offset from the eye, taken in
+        // double precision, so large world coordinates never meet in f32 and cancel
+        let offset = self.position.relative(&self.eye);
+        let offset = nalgebra_glm::vec3(offset.x(), offset.y(), offset.z());
+
         let view = nalgebra_glm::look_at_lh
         (
-            &nalgebra_glm::vec3(0.0, 0.0, 3.0),
-            &nalgebra_glm::vec3(0.0, 0.0, 0.0),
+            &nalgebra_glm::Vec3::zeros(),
+            &offset,
             &nalgebra_glm::Vec3::y(),
         );
 
@@ -618,7 +641,7 @@ impl Scene {
         (
             queue,
             0,
-            UniformBuffer { mvp: projection * view * self.model },
+            UniformBuffer { mvp: projection * view * nalgebra_glm::translation(&offset) * self.model, output: self.output.uniform() },
         );
     }
 
@@ -692,6 +715,7 @@ impl Scene {
 #[derive(Default, Debug, Copy, Clone, bytemuck::Pod, bytemuck::Zeroable)]
 struct UniformBuffer {
     mvp: nalgebra_glm::Mat4,
+    output: [f32; 4],
 }
 
 struct UniformBinding {
@@ -714,7 +738,7 @@ impl UniformBinding {
         let bind_group_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
             entries: &[wgpu::BindGroupLayoutEntry {
                 binding: 0,
-                visibility: wgpu::ShaderStages::VERTEX,
+                visibility: wgpu::ShaderStages::VERTEX | wgpu::ShaderStages::FRAGMENT,
                 ty: wgpu::BindingType::Buffer {
                     ty: wgpu::BufferBindingType::Uniform,
                     has_dynamic_offset: false,
@@ -779,6 +803,8 @@ const INDICES: [u32; 3] = [0, 1, 2]; // Clockwise winding order
 const SHADER_SOURCE: &str = "
 struct Uniform {
     mvp: mat4x4<f32>,
+    // exposure, tonemap flag, srgb encode flag, unused
+    output: vec4<f32>,
 };
 
 @group(0) @binding(0)
@@ -793,17 +819,39 @@ struct VertexOutput {
     @location(0) color: vec4<f32>,
 };
 
+fn srgb_to_linear(c: vec3<f32>) -> vec3<f32> {
+    return select(pow((c + 0.055) / 1.055, vec3<f32>(2.4)), c / 12.92, c <= vec3<f32>(0.04045));
+}
+
+fn linear_to_srgb(c: vec3<f32>) -> vec3<f32> {
+    return select(1.055 * pow(c, vec3<f32>(1.0 / 2.4)) - 0.055, c * 12.92, c <= vec3<f32>(0.0031308));
+}
+
 @vertex
 fn vertex_main(vert: VertexInput) -> VertexOutput {
     var out: VertexOutput;
-    out.color = vert.color;
+    // vertex colours are authored srgb encoded, and interpolated in linear
+    out.color = vec4<f32>(srgb_to_linear(vert.color.rgb), vert.color.a);
     out.position = ubo.mvp * vert.position;
     return out;
 };
 
 @fragment
 fn fragment_main(in: VertexOutput) -> @location(0) vec4<f32> {
-    return vec4<f32>(in.color);
+    // mirrors OutputTransform::apply on the cpu
+    var color = max(in.color.rgb * ubo.output.x, vec3<f32>(0.0));
+
+    if ubo.output.y > 0.5 {
+        color = 1.0 - exp(-color);
+    } else {
+        color = min(color, vec3<f32>(1.0));
+    }
+
+    if ubo.output.z > 0.5 {
+        color = linear_to_srgb(color);
+    }
+
+    return vec4<f32>(color, in.color.a);
 }
 ";
 
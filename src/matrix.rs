@@ -2,6 +2,13 @@
 // Luma - Matrix.rs
 
 use crate::vector::*;
+use crate::quaternion::*;
+use crate::ray::gamma;
+use crate::simd;
+
+// storage is row-major, indexed m[row][column], and vectors are rows multiplied on the left (v' = v * M), so
+// translation sits in the last row and Matrix::mul(a, b) applies a first, then b. this is the transpose of the
+// column-vector convention used by opengl and nalgebra_glm, which is why the two share a memory layout
 
 #[derive(Clone)]
 pub struct Matrix
@@ -11,6 +18,9 @@ pub struct Matrix
 
 impl Matrix
 {
+    // relative threshold on the determinant used by inverse and the decompositions
+    pub const TOLERANCE: f32 = 1e-6;
+
     pub fn new(m: [[f32; 4]; 4]) -> Matrix
     {
         return Matrix{ m: m };
@@ -26,6 +36,11 @@ impl Matrix
         return Matrix{ m: [[1.0, 0.0, 0.0, 0.0], [0.0, 1.0, 0.0, 0.0], [0.0, 0.0, 1.0, 0.0], [0.0, 0.0, 0.0, 1.0]] };
     }
 
+    pub fn get(&self, row: usize, column: usize) -> f32
+    {
+        return self.m[row][column];
+    }
+
     pub fn scale(mat1: &Matrix, scalar: f32) -> Matrix
     {
         let mut result = Matrix::null();
@@ -73,36 +88,73 @@ impl Matrix
 
     pub fn mul(mat1: &Matrix, mat2: &Matrix) -> Matrix
     {
-        let mut result = Matrix::null();
-        
-        for i in 0..4
+        return Matrix{ m: simd::matrix_mul(&mat1.m, &mat2.m) };
+    }
+
+    // full homogeneous product that keeps w as given, so w = 1 picks up translation and w = 0 doesn't
+    pub fn apply(mat1: &Matrix, vec1: &Vector) -> Vector
+    {
+        return Vector::from_raw(simd::transform(vec1.raw(), &mat1.m));
+    }
+
+    // treats the input as a point (w = 1) and divides through by the resulting w
+    pub fn transform_point(mat1: &Matrix, vec1: &Vector) -> Vector
+    {
+        let [x, y, z, w] = simd::transform([vec1.x(), vec1.y(), vec1.z(), 1.0], &mat1.m);
+
+        if w != 0.0 && w != 1.0
         {
-            for j in 0..4
-            {
-                for k in 0..4
-                {
-                    result.m[i][j] += mat1.m[i][k] * mat2.m[k][j];
-                }
-            }
+            return Vector::new(x / w, y / w, z / w, 1.0);
         }
 
-        return result;
+        return Vector::new(x, y, z, 1.0);
     }
 
-    pub fn apply(mat1: &Matrix, vec1: &Vector) -> Vector
+    // error bound of transform_point for an affine matrix: the incoming error carried through, plus the rounding
+    // of the three products and sums behind each output coordinate
+    pub fn transform_error(mat1: &Matrix, point: &Vector, error: &Vector) -> Vector
     {
-        let mut result = Vector::zero();
-        
-        for i in 0..4
+        let m = &mat1.m;
+        let p = point.raw();
+        let e = error.raw();
+
+        let mut result = [0.0; 4];
+
+        for j in 0..3
         {
-            for j in 0..4
+            let mut carried = 0.0;
+            let mut magnitude = f32::abs(m[3][j]);
+
+            for i in 0..3
             {
-                let scaled = Vector::scale(&vec1, mat1.m[i][j]);
-                result = Vector::add(&result, &scaled);
+                carried += f32::abs(m[i][j]) * e[i];
+                magnitude += f32::abs(p[i] * m[i][j]);
             }
+
+            result[j] = (1.0 + gamma(3)) * carried + gamma(3) * magnitude;
         }
 
-        return result;
+        return Vector::from_raw(result);
+    }
+
+    pub fn transform_direction(mat1: &Matrix, vec1: &Vector) -> Vector
+    {
+        // directions ignore the translation row
+        let [x, y, z, _] = simd::transform([vec1.x(), vec1.y(), vec1.z(), 0.0], &mat1.m);
+
+        return Vector::new(x, y, z, 0.0);
+    }
+
+    pub fn transform_normal(inverse: &Matrix, vec1: &Vector) -> Vector
+    {
+        let m = &inverse.m;
+
+        // normals transform by the inverse transpose so they stay perpendicular under non-uniform scale
+        let x = vec1.x() * m[0][0] + vec1.y() * m[0][1] + vec1.z() * m[0][2];
+        let y = vec1.x() * m[1][0] + vec1.y() * m[1][1] + vec1.z() * m[1][2];
+        let z = vec1.x() * m[2][0] + vec1.y() * m[2][1] + vec1.z() * m[2][2];
+
+        return Vector::new(x, y, z, 0.0);
     }
 
     pub fn transpose(mat1: &Matrix) -> Matrix
@@ -154,15 +206,20 @@ impl Matrix
         {
             for j in 0..4
             {
-                let mut submat = mat1.clone();
+                // the 3x3 submatrix left after removing row i and column j
+                let mut sub = [[0.0; 3]; 3];
 
-                for k in 0..4
+                for (r, row) in (0..4).filter(|&r| r != i).enumerate()
                 {
-                    submat.m[i][k] = 1.0;
-                    submat.m[k][j] = 1.0;
+                    for (c, column) in (0..4).filter(|&c| c != j).enumerate()
+                    {
+                        sub[r][c] = mat1.m[row][column];
+                    }
                 }
 
-                result.m[i][j] = Matrix::determinant(&submat);
+                result.m[i][j] = sub[0][0] * (sub[1][1] * sub[2][2] - sub[1][2] * sub[2][1])
+                               - sub[0][1] * (sub[1][0] * sub[2][2] - sub[1][2] * sub[2][0])
+                               + sub[0][2] * (sub[1][0] * sub[2][1] - sub[1][1] * sub[2][0]);
             }
         }
 
@@ -193,20 +250,14 @@ impl Matrix
         return Matrix::transpose(&cofactors);
     }
 
-    pub fn inverse_old(mat1: &Matrix) -> Matrix
+    pub fn inverse(mat1: &Matrix) -> Option<Matrix>
     {
-        let det = Matrix::determinant(mat1);
-
-        if det == 0.0
-        {
-            panic!("matrix is singular and cannot be inverted");
-        }
-
-        let adjugate = Matrix::adjugate(mat1);
-        return Matrix::scale(&adjugate, 1.0 / det);
+        return Matrix::inverse_tolerance(mat1, Matrix::TOLERANCE);
     }
 
-    pub fn inverse(mat1: &Matrix) -> Matrix
+    // fails when the determinant is small relative to the largest it could be for rows of the same length, which
+    // catches both exact and near singularity independently of the overall scale
+    pub fn inverse_tolerance(mat1: &Matrix, tolerance: f32) -> Option<Matrix>
     {
         let A2323 = mat1.m[2][2] * mat1.m[3][3] - mat1.m[2][3] * mat1.m[3][2];
 		let A1323 = mat1.m[2][1] * mat1.m[3][3] - mat1.m[2][3] * mat1.m[3][1];
@@ -232,6 +283,11 @@ impl Matrix
 				    + mat1.m[0][2] * (mat1.m[1][0] * A1323 - mat1.m[1][1] * A0323 + mat1.m[1][3] * A0123)
 				    - mat1.m[0][3] * (mat1.m[1][0] * A1223 - mat1.m[1][1] * A0223 + mat1.m[1][2] * A0123);
 		
+		if !Matrix::conditioned(det, &mat1.m, 4, tolerance)
+		{
+			return None;
+		}
+
 		det = 1.0 / det;
 
 		let mut out = Matrix::null();
@@ -253,8 +309,365 @@ impl Matrix
 		out.m[3][2] = det * -(mat1.m[0][0] * A1213 - mat1.m[0][1] * A0213 + mat1.m[0][2] * A0113);
 		out.m[3][3] = det *  (mat1.m[0][0] * A1212 - mat1.m[0][1] * A0212 + mat1.m[0][2] * A0112);
 	
-		return out;
+		return Some(out);
+    }
+
+    // inverts a transform whose last column is (0, 0, 0, 1) by inverting only the upper 3x3 and the translation
+    pub fn affine_inverse(mat1: &Matrix) -> Option<Matrix>
+    {
+        let inverse = Matrix::inverse3(&mat1.m, Matrix::TOLERANCE)?;
+        let t = &mat1.m[3];
+
+        let mut result = Matrix::identity();
+
+        for j in 0..3
+        {
+            for i in 0..3
+            {
+                result.m[i][j] = inverse[i][j];
+            }
+
+            // undo the translation first: t' = -t * A^-1
+            result.m[3][j] = -(t[0] * inverse[0][j] + t[1] * inverse[1][j] + t[2] * inverse[2][j]);
+        }
+
+        return Some(result);
+    }
+
+    // inverse transpose of the upper 3x3, to be used with transform_direction and renormalized afterwards
+    pub fn normal_matrix(mat1: &Matrix) -> Option<Matrix>
+    {
+        let inverse = Matrix::inverse3(&mat1.m, Matrix::TOLERANCE)?;
+
+        let mut result = Matrix::identity();
+
+        for i in 0..3
+        {
+            for j in 0..3
+            {
+                result.m[i][j] = inverse[j][i];
+            }
+        }
 
+        return Some(result);
+    }
+
+    // lu factorization with partial pivoting, failing on a pivot too small to divide by
+    pub fn lu(mat1: &Matrix) -> Option<Lu>
+    {
+        let mut a = mat1.m;
+        let mut permutation = [0, 1, 2, 3];
+        let mut sign = 1.0;
+
+        let largest = a.iter().flatten().fold(0.0, |largest: f32, x| f32::max(largest, f32::abs(*x)));
+
+        if largest == 0.0 || !largest.is_finite()
+        {
+            return None;
+        }
+
+        for k in 0..4
+        {
+            // swap the row with the largest entry in this column into the pivot position
+            let pivot = (k..4).max_by(|&i, &j| f32::abs(a[i][k]).total_cmp(&f32::abs(a[j][k])))?;
+
+            if f32::abs(a[pivot][k]) <= Matrix::TOLERANCE * largest
+            {
+                return None;
+            }
+
+            if pivot != k
+            {
+                a.swap(pivot, k);
+                permutation.swap(pivot, k);
+                sign = -sign;
+            }
+
+            // eliminate below the pivot, storing the multipliers where the zeros would go
+            for i in k + 1..4
+            {
+                a[i][k] /= a[k][k];
+
+                for j in k + 1..4
+                {
+                    a[i][j] -= a[i][k] * a[k][j];
+                }
+            }
+        }
+
+        let mut lower = Matrix::identity();
+        let mut upper = Matrix::null();
+
+        for i in 0..4
+        {
+            for j in 0..4
+            {
+                if j < i
+                {
+                    lower.m[i][j] = a[i][j];
+                }
+
+                else
+                {
+                    upper.m[i][j] = a[i][j];
+                }
+            }
+        }
+
+        return Some(Lu{ lower: lower, upper: upper, permutation: permutation, sign: sign });
+    }
+
+    // splits the upper 3x3 into a stretch followed by a rotation, so M = S * R with S symmetric and R orthogonal.
+    // unlike decompose this also handles shear, which ends up in the stretch. a mirrored transform gives an R with
+    // a determinant of -1
+    pub fn polar(mat1: &Matrix) -> Option<(Matrix, Matrix)>
+    {
+        let mut a = [[0.0; 3]; 3];
+
+        for i in 0..3
+        {
+            for j in 0..3
+            {
+                a[i][j] = mat1.m[i][j];
+            }
+        }
+
+        // newton iteration q' = (q + q^-T) / 2, which converges quadratically to the orthogonal factor
+        let mut q = a;
+
+        for _ in 0..32
+        {
+            let inverse = Matrix::inverse3(&[[q[0][0], q[0][1], q[0][2], 0.0], [q[1][0], q[1][1], q[1][2], 0.0], [q[2][0], q[2][1], q[2][2], 0.0], [0.0, 0.0, 0.0, 1.0]], Matrix::TOLERANCE)?;
+
+            let mut change: f32 = 0.0;
+
+            for i in 0..3
+            {
+                for j in 0..3
+                {
+                    let next = 0.5 * (q[i][j] + inverse[j][i]);
+                    change = f32::max(change, f32::abs(next - q[i][j]));
+                    q[i][j] = next;
+                }
+            }
+
+            if change < 1e-6
+            {
+                break;
+            }
+        }
+
+        let mut rotation = Matrix::identity();
+        let mut stretch = Matrix::identity();
+
+        for i in 0..3
+        {
+            for j in 0..3
+            {
+                rotation.m[i][j] = q[i][j];
+
+                // s = a * q^T, since the transpose of an orthogonal matrix is its inverse
+                stretch.m[i][j] = a[i][0] * q[j][0] + a[i][1] * q[j][1] + a[i][2] * q[j][2];
+            }
+        }
+
+        return Some((rotation, stretch));
+    }
+
+    // hadamard's inequality bounds |det| by the product of the row lengths, so their ratio measures how close the
+    // first n rows and columns come to being linearly dependent
+    fn conditioned(det: f32, m: &[[f32; 4]; 4], n: usize, tolerance: f32) -> bool
+    {
+        let mut bound = 1.0;
+
+        for i in 0..n
+        {
+            bound *= f32::sqrt((0..n).map(|j| m[i][j] * m[i][j]).sum::<f32>());
+        }
+
+        return det.is_finite() && bound > 0.0 && f32::abs(det) > tolerance * bound;
+    }
+
+    fn inverse3(m: &[[f32; 4]; 4], tolerance: f32) -> Option<[[f32; 3]; 3]>
+    {
+        // adjugate of the upper 3x3 via cross products of its rows
+        let c00 = m[1][1] * m[2][2] - m[1][2] * m[2][1];
+        let c01 = m[1][2] * m[2][0] - m[1][0] * m[2][2];
+        let c02 = m[1][0] * m[2][1] - m[1][1] * m[2][0];
+
+        let det = m[0][0] * c00 + m[0][1] * c01 + m[0][2] * c02;
+
+        if !Matrix::conditioned(det, m, 3, tolerance)
+        {
+            return None;
+        }
+
+        let inv = 1.0 / det;
+
+        return Some([
+            [c00 * inv, (m[0][2] * m[2][1] - m[0][1] * m[2][2]) * inv, (m[0][1] * m[1][2] - m[0][2] * m[1][1]) * inv],
+            [c01 * inv, (m[0][0] * m[2][2] - m[0][2] * m[2][0]) * inv, (m[0][2] * m[1][0] - m[0][0] * m[1][2]) * inv],
+            [c02 * inv, (m[0][1] * m[2][0] - m[0][0] * m[2][1]) * inv, (m[0][0] * m[1][1] - m[0][1] * m[1][0]) * inv],
+        ]);
+    }
+
+    pub fn translation(offset: &Vector) -> Matrix
+    {
+        return Matrix
+        {
+            m: [
+                [1.0, 0.0, 0.0, 0.0],
+                [0.0, 1.0, 0.0, 0.0],
+                [0.0, 0.0, 1.0, 0.0],
+                [offset.x(), offset.y(), offset.z(), 1.0],
+            ],
+        };
+    }
+
+    pub fn scaling(factors: &Vector) -> Matrix
+    {
+        return Matrix
+        {
+            m: [
+                [factors.x(), 0.0, 0.0, 0.0],
+                [0.0, factors.y(), 0.0, 0.0],
+                [0.0, 0.0, factors.z(), 0.0],
+                [0.0, 0.0, 0.0, 1.0],
+            ],
+        };
+    }
+
+    pub fn rotation(axis: &Vector, angle: f32) -> Matrix
+    {
+        let k = Vector::normalize(axis);
+
+        let c = f32::cos(angle);
+        let s = f32::sin(angle);
+        let t = 1.0 - c;
+
+        // rodrigues' formula, transposed for the row-vector convention
+        return Matrix
+        {
+            m: [
+                [t * k.x() * k.x() + c, t * k.x() * k.y() + s * k.z(), t * k.x() * k.z() - s * k.y(), 0.0],
+                [t * k.x() * k.y() - s * k.z(), t * k.y() * k.y() + c, t * k.y() * k.z() + s * k.x(), 0.0],
+                [t * k.x() * k.z() + s * k.y(), t * k.y() * k.z() - s * k.x(), t * k.z() * k.z() + c, 0.0],
+                [0.0, 0.0, 0.0, 1.0],
+            ],
+        };
+    }
+
+    pub fn rotation_euler(angles: &Vector) -> Matrix
+    {
+        // applied about x, then y, then z
+        let x = Matrix::rotation(&Vector::new(1.0, 0.0, 0.0, 0.0), angles.x());
+        let y = Matrix::rotation(&Vector::new(0.0, 1.0, 0.0, 0.0), angles.y());
+        let z = Matrix::rotation(&Vector::new(0.0, 0.0, 1.0, 0.0), angles.z());
+
+        return Matrix::mul(&Matrix::mul(&x, &y), &z);
+    }
+
+    // object-to-world transform that scales, then rotates, then translates
+    pub fn affine(translation: &Vector, rotation: &Vector, scale: &Vector) -> Matrix
+    {
+        let scaled = Matrix::mul(&Matrix::scaling(scale), &Matrix::rotation_euler(rotation));
+        return Matrix::mul(&scaled, &Matrix::translation(translation));
+    }
+
+    pub fn from_quaternion(q: &Quaternion) -> Matrix
+    {
+        let q = Quaternion::normalize(q);
+
+        let (xx, yy, zz) = (q.x * q.x, q.y * q.y, q.z * q.z);
+        let (xy, xz, yz) = (q.x * q.y, q.x * q.z, q.y * q.z);
+        let (wx, wy, wz) = (q.w * q.x, q.w * q.y, q.w * q.z);
+
+        // transposed like rotation, so quaternion and axis-angle matrices agree
+        return Matrix
+        {
+            m: [
+                [1.0 - 2.0 * (yy + zz), 2.0 * (xy + wz), 2.0 * (xz - wy), 0.0],
+                [2.0 * (xy - wz), 1.0 - 2.0 * (xx + zz), 2.0 * (yz + wx), 0.0],
+                [2.0 * (xz + wy), 2.0 * (yz - wx), 1.0 - 2.0 * (xx + yy), 0.0],
+                [0.0, 0.0, 0.0, 1.0],
+            ],
+        };
+    }
+
+    // expects the upper 3x3 to be a pure rotation, so decompose first when scale may be present
+    pub fn to_quaternion(mat1: &Matrix) -> Quaternion
+    {
+        // r(i, j) reads the column-vector rotation, which is the transpose of the stored one
+        let r = |i: usize, j: usize| mat1.m[j][i];
+        let trace = r(0, 0) + r(1, 1) + r(2, 2);
+
+        // shepperd's method: divide by the largest of the four candidates to stay well conditioned
+        let q = if trace > 0.0
+        {
+            let s = 2.0 * f32::sqrt(trace + 1.0);
+            Quaternion::new((r(2, 1) - r(1, 2)) / s, (r(0, 2) - r(2, 0)) / s, (r(1, 0) - r(0, 1)) / s, 0.25 * s)
+        }
+
+        else if r(0, 0) > r(1, 1) && r(0, 0) > r(2, 2)
+        {
+            let s = 2.0 * f32::sqrt(1.0 + r(0, 0) - r(1, 1) - r(2, 2));
+            Quaternion::new(0.25 * s, (r(0, 1) + r(1, 0)) / s, (r(0, 2) + r(2, 0)) / s, (r(2, 1) - r(1, 2)) / s)
+        }
+
+        else if r(1, 1) > r(2, 2)
+        {
+            let s = 2.0 * f32::sqrt(1.0 + r(1, 1) - r(0, 0) - r(2, 2));
+            Quaternion::new((r(0, 1) + r(1, 0)) / s, 0.25 * s, (r(1, 2) + r(2, 1)) / s, (r(0, 2) - r(2, 0)) / s)
+        }
+
+        else
+        {
+            let s = 2.0 * f32::sqrt(1.0 + r(2, 2) - r(0, 0) - r(1, 1));
+            Quaternion::new((r(0, 2) + r(2, 0)) / s, (r(1, 2) + r(2, 1)) / s, 0.25 * s, (r(1, 0) - r(0, 1)) / s)
+        };
+
+        return Quaternion::normalize(&q);
+    }
+
+    // same order as affine, with the rotation given as a quaternion
+    pub fn trs(translation: &Vector, rotation: &Quaternion, scale: &Vector) -> Matrix
+    {
+        let scaled = Matrix::mul(&Matrix::scaling(scale), &Matrix::from_quaternion(rotation));
+        return Matrix::mul(&scaled, &Matrix::translation(translation));
+    }
+
+    // splits an affine transform back into translation, rotation and scale, assuming no shear
+    pub fn decompose(mat1: &Matrix) -> (Vector, Quaternion, Vector)
+    {
+        let m = &mat1.m;
+
+        let translation = Vector::new(m[3][0], m[3][1], m[3][2], 0.0);
+
+        // with scale applied first, each basis row is a rotation row stretched by one scale factor
+        let mut scale = [0.0; 3];
+
+        for i in 0..3
+        {
+            scale[i] = f32::sqrt(m[i][0] * m[i][0] + m[i][1] * m[i][1] + m[i][2] * m[i][2]);
+        }
+
+        // a mirrored basis can't be a rotation, so fold the reflection into the x scale
+        if Matrix::determinant(mat1) < 0.0
+        {
+            scale[0] = -scale[0];
+        }
+
+        let mut rotation = Matrix::identity();
+
+        for i in 0..3
+        {
+            for j in 0..3
+            {
+                rotation.m[i][j] = if scale[i] != 0.0 { m[i][j] / scale[i] } else { 0.0 };
+            }
+        }
+
+        return (translation, Matrix::to_quaternion(&rotation), Vector::new(scale[0], scale[1], scale[2], 0.0));
     }
 
     pub fn lookat(eye: &Vector, at: &Vector, up: &Vector) -> Matrix
@@ -303,4 +716,494 @@ impl Matrix
         };
     }
 
-}
\ No newline at end of file
+}
+
+// factors of a row-permuted matrix, with the original row permutation[i] moved to row i so that P * M = L * U
+#[derive(Clone)]
+pub struct Lu
+{
+    // unit lower triangular
+    pub lower: Matrix,
+    pub upper: Matrix,
+    pub permutation: [usize; 4],
+
+    // determinant of the permutation, either 1 or -1
+    pub sign: f32,
+}
+
+impl Lu
+{
+    pub fn determinant(&self) -> f32
+    {
+        return self.sign * (0..4).map(|i| self.upper.m[i][i]).product::<f32>();
+    }
+
+    // finds the v for which v * M = b, matching the row-vector convention of apply
+    pub fn solve(&self, b: &Vector) -> Vector
+    {
+        let b = b.raw();
+        let l = &self.lower.m;
+        let u = &self.upper.m;
+
+        // y * U = b, forward since U is upper triangular
+        let mut y = [0.0; 4];
+
+        for j in 0..4
+        {
+            y[j] = (b[j] - (0..j).map(|i| y[i] * u[i][j]).sum::<f32>()) / u[j][j];
+        }
+
+        // w * L = y, backward since L is lower triangular with a unit diagonal
+        let mut w = [0.0; 4];
+
+        for j in (0..4).rev()
+        {
+            w[j] = y[j] - (j + 1..4).map(|i| w[i] * l[i][j]).sum::<f32>();
+        }
+
+        // w = v * P^T, so undo the row permutation
+        let mut v = [0.0; 4];
+
+        for i in 0..4
+        {
+            v[self.permutation[i]] = w[i];
+        }
+
+        return Vector::from_raw(v);
+    }
+
+    pub fn inverse(&self) -> Matrix
+    {
+        // row i of the inverse is the solution of v * M = e_i
+        let mut result = Matrix::null();
+
+        for i in 0..4
+        {
+            let mut e = [0.0; 4];
+            e[i] = 1.0;
+
+            result.m[i] = self.solve(&Vector::from_raw(e)).raw();
+        }
+
+        return result;
+    }
+}
+
+// element (row, column) of a glm matrix is element (column, row) here, which leaves the flat layout unchanged
+impl From<nalgebra_glm::Mat4> for Matrix
+{
+    fn from(mat1: nalgebra_glm::Mat4) -> Matrix
+    {
+        let mut result = Matrix::null();
+
+        for i in 0..4
+        {
+            for j in 0..4
+            {
+                result.m[i][j] = mat1[(j, i)];
+            }
+        }
+
+        return result;
+    }
+}
+
+impl From<&Matrix> for nalgebra_glm::Mat4
+{
+    fn from(mat1: &Matrix) -> nalgebra_glm::Mat4
+    {
+        return nalgebra_glm::Mat4::from_fn(|row, column| mat1.m[column][row]);
+    }
+}
+
+impl From<Matrix> for nalgebra_glm::Mat4
+{
+    fn from(mat1: Matrix) -> nalgebra_glm::Mat4
+    {
+        return nalgebra_glm::Mat4::from(&mat1);
+    }
+}
+
+#[cfg(test)]
+mod tests
+{
+    use super::*;
+
+    use rand::{Rng, SeedableRng};
+    use rand::rngs::StdRng;
+
+    const TRIALS: usize = 1000;
+
+    fn random_matrix(rng: &mut StdRng) -> Matrix
+    {
+        let mut m = [[0.0; 4]; 4];
+
+        for row in m.iter_mut()
+        {
+            for value in row.iter_mut()
+            {
+                *value = rng.random_range(-2.0..2.0);
+            }
+        }
+
+        return Matrix::new(m);
+    }
+
+    fn random_vector(rng: &mut StdRng) -> Vector
+    {
+        return Vector::new(rng.random_range(-10.0..10.0), rng.random_range(-10.0..10.0), rng.random_range(-10.0..10.0), rng.random_range(-2.0..2.0));
+    }
+
+    // relative to the magnitude once it is above one, so large entries aren't held to an absolute tolerance
+    fn close(a: f32, b: f32, tolerance: f32) -> bool
+    {
+        return f32::abs(a - b) <= tolerance * f32::max(1.0, f32::max(f32::abs(a), f32::abs(b)));
+    }
+
+    fn assert_vector(a: &Vector, b: &nalgebra_glm::Vec4, tolerance: f32)
+    {
+        for (i, (x, y)) in a.raw().iter().zip(b.iter()).enumerate()
+        {
+            assert!(close(*x, *y, tolerance), "lane {}: {} against glm {}", i, x, y);
+        }
+    }
+
+    fn assert_matrix(a: &Matrix, b: &nalgebra_glm::Mat4, tolerance: f32)
+    {
+        let a = nalgebra_glm::Mat4::from(a);
+
+        for (x, y) in a.iter().zip(b.iter())
+        {
+            assert!(close(*x, *y, tolerance), "{} against glm {}\n{}{}", x, y, a, b);
+        }
+    }
+
+    #[test]
+    fn apply_matches_glm()
+    {
+        let mut rng = StdRng::seed_from_u64(1);
+
+        for _ in 0..TRIALS
+        {
+            let m = random_matrix(&mut rng);
+            let v = random_vector(&mut rng);
+
+            // a row vector times m is the column vector times glm's transpose, which the conversion already is
+            let expected = nalgebra_glm::Mat4::from(&m) * nalgebra_glm::Vec4::from(v);
+            assert_vector(&Matrix::apply(&m, &v), &expected, 1e-5);
+        }
+    }
+
+    #[test]
+    fn transform_point_matches_glm()
+    {
+        let mut rng = StdRng::seed_from_u64(2);
+
+        for _ in 0..TRIALS
+        {
+            let affine = Matrix::affine(&random_vector(&mut rng), &random_vector(&mut rng), &Vector::new(1.5, 0.5, 2.0, 0.0));
+            let p = random_vector(&mut rng);
+
+            let expected = nalgebra_glm::Mat4::from(&affine) * nalgebra_glm::vec4(p.x(), p.y(), p.z(), 1.0);
+            assert_vector(&Matrix::transform_point(&affine, &p), &expected, 1e-5);
+
+            // projective matrices divide through by w
+            let m = random_matrix(&mut rng);
+            let h = nalgebra_glm::Mat4::from(&m) * nalgebra_glm::vec4(p.x(), p.y(), p.z(), 1.0);
+
+            if f32::abs(h.w) > 1e-2
+            {
+                assert_vector(&Matrix::transform_point(&m, &p), &nalgebra_glm::vec4(h.x / h.w, h.y / h.w, h.z / h.w, 1.0), 1e-3);
+            }
+        }
+    }
+
+    #[test]
+    fn transform_direction_matches_glm()
+    {
+        let mut rng = StdRng::seed_from_u64(3);
+
+        for _ in 0..TRIALS
+        {
+            let m = random_matrix(&mut rng);
+            let d = random_vector(&mut rng);
+
+            let expected = nalgebra_glm::Mat4::from(&m) * nalgebra_glm::vec4(d.x(), d.y(), d.z(), 0.0);
+            assert_vector(&Matrix::transform_direction(&m, &d), &nalgebra_glm::vec4(expected.x, expected.y, expected.z, 0.0), 1e-5);
+        }
+    }
+
+    #[test]
+    fn mul_matches_glm()
+    {
+        let mut rng = StdRng::seed_from_u64(4);
+
+        for _ in 0..TRIALS
+        {
+            let a = random_matrix(&mut rng);
+            let b = random_matrix(&mut rng);
+
+            // a then b for row vectors is glm's b * a
+            let expected = nalgebra_glm::Mat4::from(&b) * nalgebra_glm::Mat4::from(&a);
+            assert_matrix(&Matrix::mul(&a, &b), &expected, 1e-5);
+
+            let v = random_vector(&mut rng);
+            let chained = Matrix::apply(&b, &Matrix::apply(&a, &v));
+            assert_vector(&Matrix::apply(&Matrix::mul(&a, &b), &v), &nalgebra_glm::Vec4::from(chained), 1e-4);
+        }
+    }
+
+    #[test]
+    fn determinant_matches_glm()
+    {
+        let mut rng = StdRng::seed_from_u64(5);
+
+        for _ in 0..TRIALS
+        {
+            let m = random_matrix(&mut rng);
+            assert!(close(Matrix::determinant(&m), nalgebra_glm::determinant(&nalgebra_glm::Mat4::from(&m)), 1e-4));
+        }
+    }
+
+    #[test]
+    fn inverse_matches_glm()
+    {
+        let mut rng = StdRng::seed_from_u64(6);
+        let mut inverted = 0;
+
+        for _ in 0..TRIALS
+        {
+            let m = random_matrix(&mut rng);
+
+            let Some(inverse) = Matrix::inverse(&m) else
+            {
+                continue;
+            };
+
+            let expected = nalgebra_glm::inverse(&nalgebra_glm::Mat4::from(&m));
+            let tolerance = 1e-3 * expected.amax();
+
+            assert_matrix(&inverse, &expected, tolerance);
+            inverted += 1;
+        }
+
+        // only badly conditioned draws may be refused
+        assert!(inverted > TRIALS * 9 / 10, "inverted {} of {}", inverted, TRIALS);
+    }
+
+    #[test]
+    fn adjugate_matches_glm()
+    {
+        let mut rng = StdRng::seed_from_u64(7);
+
+        for _ in 0..TRIALS
+        {
+            let m = random_matrix(&mut rng);
+            let det = Matrix::determinant(&m);
+
+            if f32::abs(det) < 1e-1
+            {
+                continue;
+            }
+
+            // the adjugate is the determinant times the inverse, which is how glm's inverse is assembled from minors
+            let expected = nalgebra_glm::inverse(&nalgebra_glm::Mat4::from(&m)) * det;
+            assert_matrix(&Matrix::adjugate(&m), &expected, 1e-3);
+
+            let product = Matrix::mul(&m, &Matrix::adjugate(&m));
+            assert_matrix(&product, &(nalgebra_glm::Mat4::identity() * det), 1e-3);
+        }
+    }
+
+    fn random_affine(rng: &mut StdRng, reach: f32) -> Matrix
+    {
+        let translation = Vector::new(rng.random_range(-reach..reach), rng.random_range(-reach..reach), rng.random_range(-reach..reach), 0.0);
+        let rotation = random_vector(rng);
+        let scale = Vector::new(rng.random_range(0.2..3.0), rng.random_range(0.2..3.0), rng.random_range(0.2..3.0), 0.0);
+
+        return Matrix::affine(&translation, &rotation, &scale);
+    }
+
+    #[test]
+    fn affine_inverse_matches_glm()
+    {
+        let mut rng = StdRng::seed_from_u64(10);
+
+        for _ in 0..TRIALS
+        {
+            let m = random_affine(&mut rng, 100.0);
+            let inverse = Matrix::affine_inverse(&m).expect("affine transforms with a nonzero scale are invertible");
+
+            let expected = nalgebra_glm::inverse(&nalgebra_glm::Mat4::from(&m));
+            assert_matrix(&inverse, &expected, 1e-4);
+        }
+    }
+
+    #[test]
+    fn affine_inverse_ignores_translation_magnitude()
+    {
+        let far = Matrix::translation(&Vector::new(1e6, -1e6, 1e6, 0.0));
+        let inverse = Matrix::affine_inverse(&far).expect("a pure translation is always invertible");
+
+        assert_eq!(inverse.get(3, 0), -1e6);
+        assert_eq!(inverse.get(3, 1), 1e6);
+        assert_eq!(inverse.get(3, 2), -1e6);
+
+        // a zero scale is singular however it is translated
+        assert!(Matrix::affine_inverse(&Matrix::scaling(&Vector::new(1.0, 0.0, 1.0, 0.0))).is_none());
+    }
+
+    #[test]
+    fn normal_matrix_is_inverse_transpose()
+    {
+        let mut rng = StdRng::seed_from_u64(11);
+
+        for _ in 0..TRIALS
+        {
+            let m = random_affine(&mut rng, 10.0);
+            let normal = Matrix::normal_matrix(&m).expect("affine transforms with a nonzero scale are invertible");
+
+            let mut expected = nalgebra_glm::transpose(&nalgebra_glm::inverse(&nalgebra_glm::Mat4::from(&m)));
+
+            // only the upper 3x3 carries over
+            for i in 0..3
+            {
+                expected[(i, 3)] = 0.0;
+                expected[(3, i)] = 0.0;
+            }
+
+            expected[(3, 3)] = 1.0;
+            assert_matrix(&normal, &expected, 1e-4);
+
+            // a transformed tangent stays perpendicular to the transformed normal
+            let n = Vector::normalize(&random_vector(&mut rng));
+            let mut t = Vector::cross(&n, &Vector::normalize(&random_vector(&mut rng)));
+            t = Vector::new(t.x(), t.y(), t.z(), 0.0);
+
+            let dot = Vector::dot(&Matrix::transform_direction(&normal, &n), &Matrix::transform_direction(&m, &t));
+            assert!(f32::abs(dot) < 1e-3, "dot {}", dot);
+        }
+    }
+
+    #[test]
+    fn lu_factors_and_solves()
+    {
+        let mut rng = StdRng::seed_from_u64(12);
+        let mut factored = 0;
+
+        for _ in 0..TRIALS
+        {
+            let m = random_matrix(&mut rng);
+
+            let Some(lu) = Matrix::lu(&m) else
+            {
+                continue;
+            };
+
+            factored += 1;
+
+            // P * M = L * U, with row permutation[i] of M moved to row i
+            for i in 0..4
+            {
+                for j in 0..4
+                {
+                    let value: f32 = (0..4).map(|k| lu.lower.get(i, k) * lu.upper.get(k, j)).sum();
+                    assert!(close(value, m.get(lu.permutation[i], j), 1e-4));
+                }
+            }
+
+            assert!(close(lu.determinant(), Matrix::determinant(&m), 1e-3));
+
+            let v = random_vector(&mut rng);
+            let b = Matrix::apply(&m, &v);
+            let solved = lu.solve(&b);
+
+            let tolerance = 1e-3 * f32::max(1.0, nalgebra_glm::inverse(&nalgebra_glm::Mat4::from(&m)).amax());
+            assert_vector(&solved, &nalgebra_glm::Vec4::from(v), tolerance);
+
+            let expected = nalgebra_glm::inverse(&nalgebra_glm::Mat4::from(&m));
+            assert_matrix(&lu.inverse(), &expected, 1e-3 * expected.amax());
+        }
+
+        assert!(factored > TRIALS * 9 / 10, "factored {} of {}", factored, TRIALS);
+        assert!(Matrix::lu(&Matrix::null()).is_none());
+    }
+
+    #[test]
+    fn polar_splits_stretch_and_rotation()
+    {
+        let mut rng = StdRng::seed_from_u64(13);
+
+        for _ in 0..TRIALS
+        {
+            // a sheared affine transform, which decompose can't represent
+            let mut m = random_affine(&mut rng, 10.0);
+            m.m[1][0] += rng.random_range(-0.5..0.5);
+
+            let (rotation, stretch) = Matrix::polar(&m).expect("well conditioned transforms have a polar decomposition");
+
+            // rotation is orthonormal
+            let identity = Matrix::mul(&rotation, &Matrix::transpose(&rotation));
+            assert_matrix(&identity, &nalgebra_glm::Mat4::identity(), 1e-4);
+
+            // stretch is symmetric
+            for i in 0..3
+            {
+                for j in 0..3
+                {
+                    assert!(close(stretch.get(i, j), stretch.get(j, i), 1e-3));
+                }
+            }
+
+            // and together they recompose the upper 3x3 as stretch then rotation
+            let product = Matrix::mul(&stretch, &rotation);
+
+            for i in 0..3
+            {
+                for j in 0..3
+                {
+                    assert!(close(product.get(i, j), m.get(i, j), 1e-3), "{} against {}", product.get(i, j), m.get(i, j));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn matrix_glm_roundtrip()
+    {
+        let mut rng = StdRng::seed_from_u64(8);
+
+        for _ in 0..TRIALS
+        {
+            let m = random_matrix(&mut rng);
+            let glm = nalgebra_glm::Mat4::from(&m);
+
+            for row in 0..4
+            {
+                for column in 0..4
+                {
+                    // glm is indexed (row, column) in its own column-vector convention, the transpose of ours
+                    assert_eq!(glm[(column, row)], m.get(row, column));
+                }
+            }
+
+            assert!(Matrix::from(glm).m == m.m);
+            assert!(nalgebra_glm::Mat4::from(Matrix::from(glm)) == glm);
+        }
+    }
+
+    #[test]
+    fn vector_glm_roundtrip()
+    {
+        let mut rng = StdRng::seed_from_u64(9);
+
+        for _ in 0..TRIALS
+        {
+            let v = random_vector(&mut rng);
+            let glm = nalgebra_glm::Vec4::from(v);
+
+            assert_eq!((glm.x, glm.y, glm.z, glm.w), (v.x(), v.y(), v.z(), v.w()));
+            assert!(Vector::from(glm).raw() == v.raw());
+        }
+    }
+}
@@ -0,0 +1,547 @@
+// (c) 2025 Connor J. Link. All Rights Reserved.
+// Luma - packet.rs
+
+use crate::vector::*;
+use crate::ray::*;
+use crate::bounds::*;
+use crate::shape::*;
+use crate::primitive::*;
+
+#[cfg(target_arch = "x86_64")]
+use std::arch::x86_64::*;
+
+const EPSILON: f32 = 0.0001;
+
+// marks a lane that has not hit anything yet
+pub const NONE: u32 = u32::MAX;
+
+// which instructions the lanes are processed with, picked once since feature detection isn't free
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Kernel
+{
+    Scalar,
+    Sse,
+    Avx,
+}
+
+impl Kernel
+{
+    fn detect<const N: usize>() -> Kernel
+    {
+        #[cfg(target_arch = "x86_64")]
+        {
+            if N % 8 == 0 && is_x86_feature_detected!("avx")
+            {
+                return Kernel::Avx;
+            }
+
+            if N % 4 == 0
+            {
+                return Kernel::Sse;
+            }
+        }
+
+        return Kernel::Scalar;
+    }
+}
+
+// a bundle of coherent rays stored as structure-of-arrays so each component loads straight into a simd register
+#[derive(Clone)]
+pub struct Packet<const N: usize>
+{
+    origin: [[f32; N]; 3],
+    direction: [[f32; N]; 3],
+    inverse: [[f32; N]; 3],
+
+    // closest hit distance and object per lane, where a zero distance disables the lane
+    pub nearest: [f32; N],
+    pub object: [u32; N],
+
+    kernel: Kernel,
+}
+
+pub type Packet4 = Packet<4>;
+pub type Packet8 = Packet<8>;
+
+impl<const N: usize> Packet<N>
+{
+    pub fn new(rays: &[Ray]) -> Packet<N>
+    {
+        return Packet::with_kernel(rays, Kernel::detect::<N>());
+    }
+
+    // lanes without a ray, or with a zero direction, start disabled. the kernel has to come from detect, since the
+    // avx paths are only sound on a cpu that supports them
+    fn with_kernel(rays: &[Ray], kernel: Kernel) -> Packet<N>
+    {
+        let mut packet = Packet
+        {
+            origin: [[0.0; N]; 3],
+            direction: [[0.0; N]; 3],
+            inverse: [[0.0; N]; 3],
+            nearest: [0.0; N],
+            object: [NONE; N],
+            kernel: kernel,
+        };
+
+        for (lane, ray) in rays.iter().take(N).enumerate()
+        {
+            let origin = ray.origin.raw();
+            let direction = ray.direction.raw();
+
+            for axis in 0..3
+            {
+                packet.origin[axis][lane] = origin[axis];
+                packet.direction[axis][lane] = direction[axis];
+                packet.inverse[axis][lane] = 1.0 / direction[axis];
+            }
+
+            if Vector::length2(&ray.direction) > 0.0
+            {
+                packet.nearest[lane] = f32::MAX;
+            }
+        }
+
+        return packet;
+    }
+
+    pub fn hit(&self, lane: usize) -> Option<(u32, f32)>
+    {
+        if self.object[lane] == NONE
+        {
+            return None;
+        }
+
+        return Some((self.object[lane], self.nearest[lane]));
+    }
+
+    // records the sphere as the closest hit of every lane that reaches it before its current nearest
+    pub fn intersect_sphere(&mut self, sphere: &Sphere, id: u32)
+    {
+        #[cfg(target_arch = "x86_64")]
+        {
+            if self.kernel == Kernel::Avx
+            {
+                for base in (0..N).step_by(8)
+                {
+                    unsafe { self.sphere_avx(base, sphere, id) };
+                }
+
+                return;
+            }
+
+            if self.kernel == Kernel::Sse
+            {
+                for base in (0..N).step_by(4)
+                {
+                    self.sphere_sse(base, sphere, id);
+                }
+
+                return;
+            }
+        }
+
+        for lane in 0..N
+        {
+            self.sphere_scalar(lane, sphere, id);
+        }
+    }
+
+    // bit mask of the lanes whose ray enters the box before its current nearest hit
+    pub fn intersect_aabb(&self, aabb: &Aabb) -> u32
+    {
+        let mut mask = 0;
+
+        #[cfg(target_arch = "x86_64")]
+        {
+            if self.kernel == Kernel::Avx
+            {
+                for base in (0..N).step_by(8)
+                {
+                    mask |= unsafe { self.aabb_avx(base, aabb) } << base;
+                }
+
+                return mask;
+            }
+
+            if self.kernel == Kernel::Sse
+            {
+                for base in (0..N).step_by(4)
+                {
+                    mask |= self.aabb_sse(base, aabb) << base;
+                }
+
+                return mask;
+            }
+        }
+
+        for lane in 0..N
+        {
+            if self.aabb_scalar(lane, aabb)
+            {
+                mask |= 1 << lane;
+            }
+        }
+
+        return mask;
+    }
+
+    fn sphere_scalar(&mut self, lane: usize, sphere: &Sphere, id: u32)
+    {
+        let center = sphere.center.raw();
+
+        let ocx = self.origin[0][lane] - center[0];
+        let ocy = self.origin[1][lane] - center[1];
+        let ocz = self.origin[2][lane] - center[2];
+
+        let dx = self.direction[0][lane];
+        let dy = self.direction[1][lane];
+        let dz = self.direction[2][lane];
+
+        let a = dx * dx + dy * dy + dz * dz;
+        let b = ocx * dx + ocy * dy + ocz * dz;
+        let c = ocx * ocx + ocy * ocy + ocz * ocz - sphere.radius * sphere.radius;
+
+        let discriminant = b * b - a * c;
+
+        if discriminant < 0.0
+        {
+            return;
+        }
+
+        let root = f32::sqrt(discriminant);
+        let near = (-b - root) / a;
+        let far = (-b + root) / a;
+
+        let t = if near > EPSILON { near } else { far };
+
+        if t > EPSILON && t < self.nearest[lane]
+        {
+            self.nearest[lane] = t;
+            self.object[lane] = id;
+        }
+    }
+
+    fn aabb_scalar(&self, lane: usize, aabb: &Aabb) -> bool
+    {
+        let min = aabb.min.raw();
+        let max = aabb.max.raw();
+
+        let mut near = -f32::MAX;
+        let mut far = f32::MAX;
+
+        for axis in 0..3
+        {
+            let t1 = (min[axis] - self.origin[axis][lane]) * self.inverse[axis][lane];
+            let t2 = (max[axis] - self.origin[axis][lane]) * self.inverse[axis][lane];
+
+            near = f32::max(near, f32::min(t1, t2));
+            far = f32::min(far, f32::max(t1, t2));
+        }
+
+        return near <= far && far > 0.0 && near < self.nearest[lane];
+    }
+
+    #[cfg(target_arch = "x86_64")]
+    fn sphere_sse(&mut self, base: usize, sphere: &Sphere, id: u32)
+    {
+        unsafe
+        {
+            let center = sphere.center.raw();
+
+            let ocx = _mm_sub_ps(_mm_loadu_ps(self.origin[0][base..].as_ptr()), _mm_set1_ps(center[0]));
+            let ocy = _mm_sub_ps(_mm_loadu_ps(self.origin[1][base..].as_ptr()), _mm_set1_ps(center[1]));
+            let ocz = _mm_sub_ps(_mm_loadu_ps(self.origin[2][base..].as_ptr()), _mm_set1_ps(center[2]));
+
+            let dx = _mm_loadu_ps(self.direction[0][base..].as_ptr());
+            let dy = _mm_loadu_ps(self.direction[1][base..].as_ptr());
+            let dz = _mm_loadu_ps(self.direction[2][base..].as_ptr());
+
+            let a = _mm_add_ps(_mm_add_ps(_mm_mul_ps(dx, dx), _mm_mul_ps(dy, dy)), _mm_mul_ps(dz, dz));
+            let b = _mm_add_ps(_mm_add_ps(_mm_mul_ps(ocx, dx), _mm_mul_ps(ocy, dy)), _mm_mul_ps(ocz, dz));
+            let c = _mm_sub_ps(_mm_add_ps(_mm_add_ps(_mm_mul_ps(ocx, ocx), _mm_mul_ps(ocy, ocy)), _mm_mul_ps(ocz, ocz)), _mm_set1_ps(sphere.radius * sphere.radius));
+
+            let discriminant = _mm_sub_ps(_mm_mul_ps(b, b), _mm_mul_ps(a, c));
+            let root = _mm_sqrt_ps(_mm_max_ps(discriminant, _mm_setzero_ps()));
+
+            let negated = _mm_sub_ps(_mm_setzero_ps(), b);
+            let near = _mm_div_ps(_mm_sub_ps(negated, root), a);
+            let far = _mm_div_ps(_mm_add_ps(negated, root), a);
+
+            // take the near root unless it lies behind the origin
+            let epsilon = _mm_set1_ps(EPSILON);
+            let in_front = _mm_cmpgt_ps(near, epsilon);
+            let t = _mm_or_ps(_mm_and_ps(in_front, near), _mm_andnot_ps(in_front, far));
+
+            let nearest = _mm_loadu_ps(self.nearest[base..].as_ptr());
+
+            let mut hit = _mm_cmpge_ps(discriminant, _mm_setzero_ps());
+            hit = _mm_and_ps(hit, _mm_cmpgt_ps(t, epsilon));
+            hit = _mm_and_ps(hit, _mm_cmplt_ps(t, nearest));
+
+            _mm_storeu_ps(self.nearest[base..].as_mut_ptr(), _mm_or_ps(_mm_and_ps(hit, t), _mm_andnot_ps(hit, nearest)));
+
+            let mask = _mm_movemask_ps(hit);
+
+            for lane in 0..4
+            {
+                if mask & (1 << lane) != 0
+                {
+                    self.object[base + lane] = id;
+                }
+            }
+        }
+    }
+
+    #[cfg(target_arch = "x86_64")]
+    fn aabb_sse(&self, base: usize, aabb: &Aabb) -> u32
+    {
+        unsafe
+        {
+            let min = aabb.min.raw();
+            let max = aabb.max.raw();
+
+            let mut near = _mm_set1_ps(-f32::MAX);
+            let mut far = _mm_set1_ps(f32::MAX);
+
+            for axis in 0..3
+            {
+                let origin = _mm_loadu_ps(self.origin[axis][base..].as_ptr());
+                let inverse = _mm_loadu_ps(self.inverse[axis][base..].as_ptr());
+
+                let t1 = _mm_mul_ps(_mm_sub_ps(_mm_set1_ps(min[axis]), origin), inverse);
+                let t2 = _mm_mul_ps(_mm_sub_ps(_mm_set1_ps(max[axis]), origin), inverse);
+
+                near = _mm_max_ps(near, _mm_min_ps(t1, t2));
+                far = _mm_min_ps(far, _mm_max_ps(t1, t2));
+            }
+
+            let nearest = _mm_loadu_ps(self.nearest[base..].as_ptr());
+
+            let mut hit = _mm_cmple_ps(near, far);
+            hit = _mm_and_ps(hit, _mm_cmpgt_ps(far, _mm_setzero_ps()));
+            hit = _mm_and_ps(hit, _mm_cmplt_ps(near, nearest));
+
+            return _mm_movemask_ps(hit) as u32;
+        }
+    }
+
+    #[cfg(target_arch = "x86_64")]
+    #[target_feature(enable = "avx")]
+    unsafe fn sphere_avx(&mut self, base: usize, sphere: &Sphere, id: u32)
+    {
+        unsafe
+        {
+            let center = sphere.center.raw();
+
+            let ocx = _mm256_sub_ps(_mm256_loadu_ps(self.origin[0][base..].as_ptr()), _mm256_set1_ps(center[0]));
+            let ocy = _mm256_sub_ps(_mm256_loadu_ps(self.origin[1][base..].as_ptr()), _mm256_set1_ps(center[1]));
+            let ocz = _mm256_sub_ps(_mm256_loadu_ps(self.origin[2][base..].as_ptr()), _mm256_set1_ps(center[2]));
+
+            let dx = _mm256_loadu_ps(self.direction[0][base..].as_ptr());
+            let dy = _mm256_loadu_ps(self.direction[1][base..].as_ptr());
+            let dz = _mm256_loadu_ps(self.direction[2][base..].as_ptr());
+
+            let a = _mm256_add_ps(_mm256_add_ps(_mm256_mul_ps(dx, dx), _mm256_mul_ps(dy, dy)), _mm256_mul_ps(dz, dz));
+            let b = _mm256_add_ps(_mm256_add_ps(_mm256_mul_ps(ocx, dx), _mm256_mul_ps(ocy, dy)), _mm256_mul_ps(ocz, dz));
+            let c = _mm256_sub_ps(_mm256_add_ps(_mm256_add_ps(_mm256_mul_ps(ocx, ocx), _mm256_mul_ps(ocy, ocy)), _mm256_mul_ps(ocz, ocz)), _mm256_set1_ps(sphere.radius * sphere.radius));
+
+            let discriminant = _mm256_sub_ps(_mm256_mul_ps(b, b), _mm256_mul_ps(a, c));
+            let root = _mm256_sqrt_ps(_mm256_max_ps(discriminant, _mm256_setzero_ps()));
+
+            let negated = _mm256_sub_ps(_mm256_setzero_ps(), b);
+            let near = _mm256_div_ps(_mm256_sub_ps(negated, root), a);
+            let far = _mm256_div_ps(_mm256_add_ps(negated, root), a);
+
+            let epsilon = _mm256_set1_ps(EPSILON);
+            let t = _mm256_blendv_ps(far, near, _mm256_cmp_ps::<_CMP_GT_OQ>(near, epsilon));
+
+            let nearest = _mm256_loadu_ps(self.nearest[base..].as_ptr());
+
+            let mut hit = _mm256_cmp_ps::<_CMP_GE_OQ>(discriminant, _mm256_setzero_ps());
+            hit = _mm256_and_ps(hit, _mm256_cmp_ps::<_CMP_GT_OQ>(t, epsilon));
+            hit = _mm256_and_ps(hit, _mm256_cmp_ps::<_CMP_LT_OQ>(t, nearest));
+
+            _mm256_storeu_ps(self.nearest[base..].as_mut_ptr(), _mm256_blendv_ps(nearest, t, hit));
+
+            let mask = _mm256_movemask_ps(hit);
+
+            for lane in 0..8
+            {
+                if mask & (1 << lane) != 0
+                {
+                    self.object[base + lane] = id;
+                }
+            }
+        }
+    }
+
+    #[cfg(target_arch = "x86_64")]
+    #[target_feature(enable = "avx")]
+    unsafe fn aabb_avx(&self, base: usize, aabb: &Aabb) -> u32
+    {
+        unsafe
+        {
+            let min = aabb.min.raw();
+            let max = aabb.max.raw();
+
+            let mut near = _mm256_set1_ps(-f32::MAX);
+            let mut far = _mm256_set1_ps(f32::MAX);
+
+            for axis in 0..3
+            {
+                let origin = _mm256_loadu_ps(self.origin[axis][base..].as_ptr());
+                let inverse = _mm256_loadu_ps(self.inverse[axis][base..].as_ptr());
+
+                let t1 = _mm256_mul_ps(_mm256_sub_ps(_mm256_set1_ps(min[axis]), origin), inverse);
+                let t2 = _mm256_mul_ps(_mm256_sub_ps(_mm256_set1_ps(max[axis]), origin), inverse);
+
+                near = _mm256_max_ps(near, _mm256_min_ps(t1, t2));
+                far = _mm256_min_ps(far, _mm256_max_ps(t1, t2));
+            }
+
+            let nearest = _mm256_loadu_ps(self.nearest[base..].as_ptr());
+
+            let mut hit = _mm256_cmp_ps::<_CMP_LE_OQ>(near, far);
+            hit = _mm256_and_ps(hit, _mm256_cmp_ps::<_CMP_GT_OQ>(far, _mm256_setzero_ps()));
+            hit = _mm256_and_ps(hit, _mm256_cmp_ps::<_CMP_LT_OQ>(near, nearest));
+
+            return _mm256_movemask_ps(hit) as u32;
+        }
+    }
+}
+
+// closest sphere for every ray, tracing the rays in packets of n and skipping spheres whose box no lane reaches
+pub fn trace<const N: usize>(rays: &[Ray], spheres: &[Sphere], boxes: &[Aabb], hits: &mut Vec<Option<(u32, f32)>>)
+{
+    hits.clear();
+
+    let kernel = Kernel::detect::<N>();
+
+    for chunk in rays.chunks(N)
+    {
+        let mut packet = Packet::<N>::with_kernel(chunk, kernel);
+
+        for (id, (sphere, aabb)) in spheres.iter().zip(boxes).enumerate()
+        {
+            if packet.intersect_aabb(aabb) == 0
+            {
+                continue;
+            }
+
+            packet.intersect_sphere(sphere, id as u32);
+        }
+
+        for lane in 0..chunk.len()
+        {
+            hits.push(packet.hit(lane));
+        }
+    }
+}
+
+// closest hit of every ray against arbitrary shapes given by their world bounds and an exact test. each box is
+// culled a packet at a time, and only the lanes that reach it before their nearest hit so far run the exact test.
+// unbounded shapes such as planes can't be culled, so every live lane tests them
+pub fn trace_shapes<const N: usize, F>(rays: &[Ray], boxes: &[Aabb], intersect: F, hits: &mut Vec<Option<(u32, Hit)>>)
+where
+    F: Fn(usize, &Ray) -> Option<Hit>,
+{
+    hits.clear();
+
+    let kernel = Kernel::detect::<N>();
+
+    // the exact tests run in each shape's own space and round differently from the slab test against the world
+    // box, so a ray grazing an edge could hit the shape yet just miss its box. a small pad keeps the cull conservative
+    let padded: Vec<Aabb> = boxes.iter().map(|aabb|
+    {
+        if !aabb.is_finite()
+        {
+            return *aabb;
+        }
+
+        let center = aabb.center();
+        let reach = Vector::length(&aabb.extent()) + Vector::length(&Vector::new(center.x(), center.y(), center.z(), 0.0));
+
+        Aabb::expand(aabb, 1e-4 * reach)
+    }).collect();
+
+    for chunk in rays.chunks(N)
+    {
+        let mut packet = Packet::<N>::with_kernel(chunk, kernel);
+        let mut nearest: [Option<Hit>; N] = [None; N];
+
+        let live = (0..chunk.len()).filter(|&lane| packet.nearest[lane] > 0.0).fold(0, |mask, lane| mask | 1 << lane);
+
+        for (id, aabb) in padded.iter().enumerate()
+        {
+            let mask = if aabb.is_finite() { packet.intersect_aabb(aabb) } else { live };
+
+            for lane in 0..chunk.len()
+            {
+                if mask & (1 << lane) == 0
+                {
+                    continue;
+                }
+
+                let Some(hit) = intersect(id, &chunk[lane]) else
+                {
+                    continue;
+                };
+
+                if hit.distance < packet.nearest[lane]
+                {
+                    // tightens the culling of the boxes still to come
+                    packet.nearest[lane] = hit.distance;
+                    packet.object[lane] = id as u32;
+                    nearest[lane] = Some(hit);
+                }
+            }
+        }
+
+        for lane in 0..chunk.len()
+        {
+            hits.push(nearest[lane].map(|hit| (packet.object[lane], hit)));
+        }
+    }
+}
+
+fn time<const N: usize>(rays: &[Ray], spheres: &[Sphere], boxes: &[Aabb], iterations: u32, hits: &mut Vec<Option<(u32, f32)>>) -> f32
+{
+    let now = std::time::Instant::now();
+
+    for _ in 0..iterations
+    {
+        trace::<N>(rays, spheres, boxes, hits);
+    }
+
+    return now.elapsed().as_secs_f32() / iterations as f32;
+}
+
+// times single rays against 4- and 8-wide packets on the same work and checks that they agree
+pub fn benchmark(rays: &[Ray], spheres: &[Sphere], iterations: u32) -> String
+{
+    let boxes: Vec<Aabb> = spheres.iter().map(|sphere|
+    {
+        let extent = Vector::new(sphere.radius, sphere.radius, sphere.radius, 0.0);
+        Aabb::new(sphere.center - extent, sphere.center + extent)
+    }).collect();
+
+    let iterations = iterations.max(1);
+
+    let mut single = Vec::new();
+    let mut hits = Vec::new();
+
+    let scalar = time::<1>(rays, spheres, &boxes, iterations, &mut single);
+    let packet4 = time::<4>(rays, spheres, &boxes, iterations, &mut hits);
+    let agree4 = hits.iter().zip(&single).all(|(a, b)| a.map(|h| h.0) == b.map(|h| h.0));
+    let packet8 = time::<8>(rays, spheres, &boxes, iterations, &mut hits);
+    let agree8 = hits.iter().zip(&single).all(|(a, b)| a.map(|h| h.0) == b.map(|h| h.0));
+
+    let found = single.iter().filter(|h| h.is_some()).count();
+
+    return format!("{} rays, {} spheres, {} hits: single {:.3} ms, 4-wide {:.3} ms ({:.2}x{}), 8-wide {:.3} ms ({:.2}x{})",
+        rays.len(), spheres.len(), found,
+        1000.0 * scalar,
+        1000.0 * packet4, scalar / packet4, if agree4 { "" } else { ", mismatch" },
+        1000.0 * packet8, scalar / packet8, if agree8 { "" } else { ", mismatch" });
+}
@@ -0,0 +1,239 @@
+// (c) 2025 Connor J. Link. All Rights Reserved.
+// Luma - path.rs
+
+use crate::camera::*;
+use crate::quaternion::*;
+use crate::animation::*;
+use crate::world::*;
+
+#[derive(Clone, Copy, Debug)]
+pub struct PathSample
+{
+    pub time: f32,
+    pub position: WorldPosition,
+    pub orientation: Quaternion,
+    pub fov: f32,
+}
+
+// camera poses captured over time, stored one sample per line as:
+//   <time> <x> <y> <z> <qx> <qy> <qz> <qw> <fov>
+// older paths written as <time> <x> <y> <z> <yaw> <pitch> <fov> still load, without roll
+#[derive(Clone)]
+pub struct CameraPath
+{
+    samples: Vec<PathSample>,
+}
+
+impl CameraPath
+{
+    pub fn new() -> CameraPath
+    {
+        return CameraPath{ samples: Vec::new() };
+    }
+
+    pub fn samples(&self) -> &Vec<PathSample>
+    {
+        return &self.samples;
+    }
+
+    pub fn is_empty(&self) -> bool
+    {
+        return self.samples.is_empty();
+    }
+
+    pub fn duration(&self) -> f32
+    {
+        return self.samples.last().map_or(0.0, |s| s.time);
+    }
+
+    // appends the camera pose in world space, dropping samples that would run backwards in time
+    pub fn record(&mut self, time: f32, camera: &Camera, origin: &FloatingOrigin)
+    {
+        if self.samples.last().is_some_and(|s| time <= s.time)
+        {
+            return;
+        }
+
+        self.samples.push(PathSample{ time: time, position: origin.to_world(&camera.position()), orientation: camera.orientation(), fov: camera.fov() });
+    }
+
+    pub fn sample(&self, time: f32) -> Option<PathSample>
+    {
+        let first = self.samples.first()?;
+        let last = self.samples.last()?;
+
+        if time <= first.time
+        {
+            return Some(*first);
+        }
+
+        if time >= last.time
+        {
+            return Some(*last);
+        }
+
+        let next = self.samples.partition_point(|s| s.time <= time);
+        let sample1 = &self.samples[next - 1];
+        let sample2 = &self.samples[next];
+
+        let t = (time - sample1.time) / (sample2.time - sample1.time);
+
+        return Some(PathSample
+        {
+            time: time,
+            position: Animatable::lerp(&sample1.position, &sample2.position, t),
+            orientation: Animatable::lerp(&sample1.orientation, &sample2.orientation, t),
+            fov: Animatable::lerp(&sample1.fov, &sample2.fov, t),
+        });
+    }
+
+    // linear camera tracks so the path can drive the animation system and sequence output. the orientation track
+    // carries yaw, pitch and roll together, so the angle tracks are left empty
+    pub fn to_tracks(&self) -> CameraTracks
+    {
+        let mut tracks = CameraTracks{ position: Track::new(), yaw: Track::new(), pitch: Track::new(), fov: Track::new(), orientation: Track::new() };
+
+        for sample in &self.samples
+        {
+            tracks.position.insert(Keyframe::new(sample.time, sample.position, Interpolation::Linear));
+            tracks.orientation.insert(Keyframe::new(sample.time, sample.orientation, Interpolation::Linear));
+            tracks.fov.insert(Keyframe::new(sample.time, sample.fov, Interpolation::Linear));
+        }
+
+        return tracks;
+    }
+
+    pub fn parse(text: &str) -> std::io::Result<CameraPath>
+    {
+        let mut path = CameraPath::new();
+
+        for (index, raw) in text.lines().enumerate()
+        {
+            let content = raw.split('#').next().unwrap_or("");
+            let fields: Vec<&str> = content.split_whitespace().collect();
+
+            if fields.is_empty()
+            {
+                continue;
+            }
+
+            // read in double precision so positions far from the origin survive the round trip
+            let values: Vec<f64> = fields.iter().filter_map(|f| f.parse::<f64>().ok()).collect();
+
+            if values.len() != fields.len() || (values.len() != 9 && values.len() != 7)
+            {
+                return Err(std::io::Error::new(std::io::ErrorKind::InvalidData, format!("line {}: expected 9 numbers", index + 1)));
+            }
+
+            let orientation = if values.len() == 9
+            {
+                Quaternion::normalize(&Quaternion::new(values[4] as f32, values[5] as f32, values[6] as f32, values[7] as f32))
+            }
+
+            else
+            {
+                Camera::compose(values[4] as f32, values[5] as f32, 0.0)
+            };
+
+            let position = WorldPosition::new(values[1], values[2], values[3]);
+            let fov = values[values.len() - 1] as f32;
+
+            path.samples.push(PathSample{ time: values[0] as f32, position: position, orientation: orientation, fov: fov });
+        }
+
+        path.samples.sort_by(|a, b| a.time.total_cmp(&b.time));
+
+        return Ok(path);
+    }
+
+    pub fn serialize(&self) -> String
+    {
+        let mut text = String::from("# time x y z qx qy qz qw fov\n");
+
+        for sample in &self.samples
+        {
+            let position = sample.position;
+            let orientation = sample.orientation;
+
+            text.push_str(&format!("{} {} {} {} {} {} {} {} {}\n", sample.time, position.x, position.y, position.z,
+                orientation.x, orientation.y, orientation.z, orientation.w, sample.fov));
+        }
+
+        return text;
+    }
+
+    pub fn load(path: &str) -> std::io::Result<CameraPath>
+    {
+        let text = std::fs::read_to_string(path)?;
+        return CameraPath::parse(&text);
+    }
+
+    pub fn save(&self, path: &str) -> std::io::Result<()>
+    {
+        return std::fs::write(path, self.serialize());
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum PlaybackRate
+{
+    // follows the wall clock, so slow frames skip ahead along the path
+    Realtime,
+
+    // advances by a fixed step per rendered frame regardless of how long it took
+    Fixed{ fps: f32 },
+
+    // advances by a fixed step once the given number of samples has been accumulated
+    Samples{ fps: f32, samples: u32 },
+}
+
+// frame times gathered while playing a path back
+#[derive(Clone)]
+pub struct Timings
+{
+    pub frames: Vec<f32>,
+}
+
+impl Timings
+{
+    pub fn new() -> Timings
+    {
+        return Timings{ frames: Vec::new() };
+    }
+
+    pub fn total(&self) -> f32
+    {
+        return self.frames.iter().sum();
+    }
+
+    pub fn mean(&self) -> f32
+    {
+        if self.frames.is_empty()
+        {
+            return 0.0;
+        }
+
+        return self.total() / self.frames.len() as f32;
+    }
+
+    // nearest-rank percentile in the range 0 to 100
+    pub fn percentile(&self, percent: f32) -> f32
+    {
+        if self.frames.is_empty()
+        {
+            return 0.0;
+        }
+
+        let mut sorted = self.frames.clone();
+        sorted.sort_by(|a, b| a.total_cmp(b));
+
+        let rank = f32::ceil(f32::clamp(percent, 0.0, 100.0) / 100.0 * sorted.len() as f32) as usize;
+        return sorted[rank.saturating_sub(1)];
+    }
+
+    pub fn summary(&self) -> String
+    {
+        return format!("{} frames, {:.3} s total, mean {:.2} ms, median {:.2} ms, 99th percentile {:.2} ms",
+            self.frames.len(), self.total(), 1000.0 * self.mean(), 1000.0 * self.percentile(50.0), 1000.0 * self.percentile(99.0));
+    }
+}
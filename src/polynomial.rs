@@ -0,0 +1,230 @@
+// (c) 2025 Connor J. Link. All Rights Reserved.
+// Luma - polynomial.rs
+
+// closed-form real root solvers after schwarze (graphics gems i), with coefficients in ascending order
+
+const EPSILON: f64 = 1e-9;
+
+fn is_zero(x: f64) -> bool
+{
+    return x > -EPSILON && x < EPSILON;
+}
+
+pub fn quadratic(c: [f64; 3], roots: &mut [f64]) -> usize
+{
+    // degenerates to a line, such as for a ray parallel to a cone's generator, which crosses the surface only once
+    if c[2] == 0.0
+    {
+        if c[1] == 0.0
+        {
+            return 0;
+        }
+
+        roots[0] = -c[0] / c[1];
+        return 1;
+    }
+
+    // normal form: x^2 + px + q = 0
+    let p = c[1] / (2.0 * c[2]);
+    let q = c[0] / c[2];
+
+    let d = p * p - q;
+
+    // a double root is reported twice, so a grazing ray still enters and leaves the surface
+    if is_zero(d)
+    {
+        roots[0] = -p;
+        roots[1] = -p;
+        return 2;
+    }
+
+    if d < 0.0
+    {
+        return 0;
+    }
+
+    let sqrt_d = f64::sqrt(d);
+
+    // the root where -p and the square root add, then the other from their product q, avoiding cancellation
+    roots[0] = -p - f64::copysign(sqrt_d, p);
+    roots[1] = q / roots[0];
+
+    return 2;
+}
+
+pub fn cubic(c: [f64; 4], roots: &mut [f64]) -> usize
+{
+    // normal form: x^3 + ax^2 + bx + c = 0
+    let a = c[2] / c[3];
+    let b = c[1] / c[3];
+    let cc = c[0] / c[3];
+
+    // substitute x = y - a/3 to eliminate the quadratic term: y^3 + py + q = 0
+    let a_squared = a * a;
+    let p = 1.0 / 3.0 * (-1.0 / 3.0 * a_squared + b);
+    let q = 1.0 / 2.0 * (2.0 / 27.0 * a * a_squared - 1.0 / 3.0 * a * b + cc);
+
+    // cardano's formula
+    let p_cubed = p * p * p;
+    let d = q * q + p_cubed;
+
+    let count;
+
+    if is_zero(d)
+    {
+        if is_zero(q)
+        {
+            // one triple root
+            roots[0] = 0.0;
+            count = 1;
+        }
+
+        else
+        {
+            // one single and one double root
+            let u = f64::cbrt(-q);
+            roots[0] = 2.0 * u;
+            roots[1] = -u;
+            count = 2;
+        }
+    }
+
+    else if d < 0.0
+    {
+        // casus irreducibilis: three real roots
+        let phi = 1.0 / 3.0 * f64::acos(-q / f64::sqrt(-p_cubed));
+        let t = 2.0 * f64::sqrt(-p);
+
+        roots[0] = t * f64::cos(phi);
+        roots[1] = -t * f64::cos(phi + std::f64::consts::PI / 3.0);
+        roots[2] = -t * f64::cos(phi - std::f64::consts::PI / 3.0);
+        count = 3;
+    }
+
+    else
+    {
+        // one real root
+        let sqrt_d = f64::sqrt(d);
+        let u = f64::cbrt(sqrt_d - q);
+        let v = -f64::cbrt(sqrt_d + q);
+
+        roots[0] = u + v;
+        count = 1;
+    }
+
+    let shift = 1.0 / 3.0 * a;
+
+    for root in roots.iter_mut().take(count)
+    {
+        *root -= shift;
+    }
+
+    return count;
+}
+
+pub fn quartic(c: [f64; 5], roots: &mut [f64]) -> usize
+{
+    // normal form: x^4 + ax^3 + bx^2 + cx + d = 0
+    let a = c[3] / c[4];
+    let b = c[2] / c[4];
+    let cc = c[1] / c[4];
+    let d = c[0] / c[4];
+
+    // substitute x = y - a/4 to eliminate the cubic term: y^4 + py^2 + qy + r = 0
+    let a_squared = a * a;
+    let p = -3.0 / 8.0 * a_squared + b;
+    let q = 1.0 / 8.0 * a_squared * a - 1.0 / 2.0 * a * b + cc;
+    let r = -3.0 / 256.0 * a_squared * a_squared + 1.0 / 16.0 * a_squared * b - 1.0 / 4.0 * a * cc + d;
+
+    let mut count;
+
+    if is_zero(r)
+    {
+        // no absolute term: y(y^3 + py + q) = 0
+        count = cubic([q, p, 0.0, 1.0], roots);
+        roots[count] = 0.0;
+        count += 1;
+    }
+
+    else
+    {
+        // solve the resolvent cubic and take its one guaranteed real root
+        let mut resolvent = [0.0; 3];
+        cubic([1.0 / 2.0 * r * p - 1.0 / 8.0 * q * q, -r, -1.0 / 2.0 * p, 1.0], &mut resolvent);
+
+        let z = resolvent[0];
+
+        // to build two quadratic equations
+        let mut u = z * z - r;
+        let mut v = 2.0 * z - p;
+
+        if is_zero(u)
+        {
+            u = 0.0;
+        }
+
+        else if u > 0.0
+        {
+            u = f64::sqrt(u);
+        }
+
+        else
+        {
+            return 0;
+        }
+
+        if is_zero(v)
+        {
+            v = 0.0;
+        }
+
+        else if v > 0.0
+        {
+            v = f64::sqrt(v);
+        }
+
+        else
+        {
+            return 0;
+        }
+
+        count = quadratic([z - u, if q < 0.0 { -v } else { v }, 1.0], roots);
+        count += quadratic([z + u, if q < 0.0 { v } else { -v }, 1.0], &mut roots[count..]);
+    }
+
+    let shift = 1.0 / 4.0 * a;
+
+    for root in roots.iter_mut().take(count)
+    {
+        *root -= shift;
+    }
+
+    return count;
+}
+
+pub fn evaluate(c: &[f64], x: f64) -> f64
+{
+    // horner's scheme
+    return c.iter().rev().fold(0.0, |sum, coefficient| sum * x + coefficient);
+}
+
+pub fn polish(c: &[f64], root: f64, iterations: u32) -> f64
+{
+    let mut x = root;
+
+    // newton refinement against the original polynomial to recover precision lost in the closed form
+    for _ in 0..iterations
+    {
+        let value = evaluate(c, x);
+        let slope = c.iter().enumerate().skip(1).rev().fold(0.0, |sum, (i, coefficient)| sum * x + i as f64 * coefficient);
+
+        if slope == 0.0
+        {
+            break;
+        }
+
+        x -= value / slope;
+    }
+
+    return x;
+}
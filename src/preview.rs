@@ -0,0 +1,49 @@
+// (c) 2025 Connor J. Link. All Rights Reserved.
+// Luma - preview.rs
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum PreviewMode
+{
+    // traces one pixel per block and stretches it over the whole block
+    Downscale,
+
+    // traces alternating halves of a checkerboard and fills the gaps from a traced neighbour
+    Checkerboard,
+}
+
+#[derive(Clone)]
+#[derive(Copy)]
+#[derive(Debug)]
+pub struct Preview
+{
+    pub enabled: bool,
+    pub mode: PreviewMode,
+
+    // coarsest block size in pixels, rounded down to a power of two
+    pub max_stride: usize,
+
+    // seconds a preview frame may take before the block size grows
+    pub budget: f32,
+}
+
+impl Preview
+{
+    pub fn new() -> Preview
+    {
+        return Preview{ enabled: true, mode: PreviewMode::Downscale, max_stride: 8, budget: 1.0 / 30.0 };
+    }
+
+    // finest power-of-two block size whose estimated frame time fits the budget
+    pub fn stride(&self, pixel_cost: f32, pixels: usize) -> usize
+    {
+        let max_stride = 1 << usize::max(self.max_stride, 1).ilog2();
+        let mut stride = 1;
+
+        while stride < max_stride && (pixels / (stride * stride)) as f32 * pixel_cost > self.budget
+        {
+            stride *= 2;
+        }
+
+        return usize::min(stride, max_stride);
+    }
+}
@@ -0,0 +1,741 @@
+// (c) 2025 Connor J. Link. All Rights Reserved.
+// Luma - primitive.rs
+
+use std::f32::consts::PI;
+
+use crate::vector::*;
+use crate::ray::*;
+use crate::shape::*;
+use crate::bounds::*;
+use crate::polynomial;
+
+fn at(ray: &Ray, t: f32) -> Vector
+{
+    return Vector::add(&ray.origin, &Vector::scale(&ray.direction, t));
+}
+
+// both crossings of the line with a sphere, accurate even where the quadratic coefficients are huge and nearly
+// equal, as for a ray leaving the surface of the large ground sphere
+fn sphere_roots(ray: &Ray, center: &Vector, radius: f32) -> Option<(f32, f32)>
+{
+    let o = ray.origin.raw();
+    let d = ray.direction.raw();
+    let c = center.raw();
+
+    let f = [o[0] as f64 - c[0] as f64, o[1] as f64 - c[1] as f64, o[2] as f64 - c[2] as f64];
+    let d = [d[0] as f64, d[1] as f64, d[2] as f64];
+    let r = radius as f64;
+
+    let a = d[0] * d[0] + d[1] * d[1] + d[2] * d[2];
+    let b = f[0] * d[0] + f[1] * d[1] + f[2] * d[2];
+    let c = f[0] * f[0] + f[1] * f[1] + f[2] * f[2] - r * r;
+
+    if a == 0.0
+    {
+        return None;
+    }
+
+    // hearn and baker: b^2 - ac equals a(r^2 - l^2), with l the distance from the center to the line
+    let l = [f[0] - d[0] * b / a, f[1] - d[1] * b / a, f[2] - d[2] * b / a];
+    let l = f64::sqrt(l[0] * l[0] + l[1] * l[1] + l[2] * l[2]);
+
+    let discriminant = a * (r - l) * (r + l);
+
+    if discriminant < 0.0
+    {
+        return None;
+    }
+
+    let q = -(b + f64::copysign(f64::sqrt(discriminant), b));
+
+    let t1 = (q / a) as f32;
+    let t2 = if q != 0.0 { (c / q) as f32 } else { t1 };
+
+    return Some((f32::min(t1, t2), f32::max(t1, t2)));
+}
+
+// builds a tangent frame around a unit normal (duff et al. 2017)
+fn orthonormal(normal: &Vector) -> (Vector, Vector)
+{
+    let sign = f32::copysign(1.0, normal.z());
+    let a = -1.0 / (sign + normal.z());
+    let b = normal.x() * normal.y() * a;
+
+    let tangent = Vector::new(1.0 + sign * normal.x() * normal.x() * a, sign * b, -sign * normal.x(), 0.0);
+    let bitangent = Vector::new(b, sign + normal.y() * normal.y() * a, -normal.y(), 0.0);
+
+    return (tangent, bitangent);
+}
+
+// angle around the y axis mapped onto [0, 1]
+fn azimuth(x: f32, z: f32) -> f32
+{
+    return (f32::atan2(z, x) + PI) / (2.0 * PI);
+}
+
+pub struct Sphere
+{
+    pub center: Vector,
+    pub radius: f32,
+}
+
+impl Sphere
+{
+    pub fn new(center: Vector, radius: f32) -> Sphere
+    {
+        return Sphere{ center: center, radius: radius };
+    }
+
+    pub fn unit() -> Sphere
+    {
+        return Sphere{ center: Vector::new(0.0, 0.0, 0.0, 1.0), radius: 1.0 };
+    }
+}
+
+impl Shape for Sphere
+{
+    fn intersect(&self, ray: &Ray) -> Option<Hit>
+    {
+        let (t1, t2) = sphere_roots(ray, &self.center, self.radius)?;
+
+        // a ray starting inside the sphere still hits it on the way out
+        let distance = if t1 > 0.0 { t1 } else if t2 > 0.0 { t2 } else { return None; };
+
+        let toward = Vector::sub(&at(ray, distance), &self.center);
+        let normal = Vector::normalize(&toward);
+
+        // reprojecting onto the surface leaves only the error of the few operations below, however far the ray went
+        let hit = Vector::add(&self.center, &Vector::scale(&normal, self.radius));
+        let error = Vector::new
+        (
+            gamma(5) * (f32::abs(self.center.x()) + f32::abs(self.radius * normal.x())),
+            gamma(5) * (f32::abs(self.center.y()) + f32::abs(self.radius * normal.y())),
+            gamma(5) * (f32::abs(self.center.z()) + f32::abs(self.radius * normal.z())),
+            0.0,
+        );
+
+        let uv = Vector::new(azimuth(normal.x(), normal.z()), f32::acos(f32::clamp(normal.y(), -1.0, 1.0)) / PI, 0.0, 0.0);
+
+        return Some(Hit{ position: Vector::new(hit.x(), hit.y(), hit.z(), 1.0), normal: normal, uv: uv, distance: distance, exit: t2, error: error });
+    }
+
+    fn bounds(&self) -> Aabb
+    {
+        let extent = Vector::new(self.radius, self.radius, self.radius, 0.0);
+        return Aabb::new(Vector::sub(&self.center, &extent), Vector::add(&self.center, &extent));
+    }
+
+    fn spans(&self, ray: &Ray, spans: &mut Vec<Span>)
+    {
+        let Some((t1, t2)) = sphere_roots(ray, &self.center, self.radius) else
+        {
+            return;
+        };
+
+        let crossing = |t: f32|
+        {
+            let normal = Vector::normalize(&Vector::sub(&at(ray, t), &self.center));
+            let uv = Vector::new(azimuth(normal.x(), normal.z()), f32::acos(f32::clamp(normal.y(), -1.0, 1.0)) / PI, 0.0, 0.0);
+            return Crossing::new(t, normal, uv);
+        };
+
+        spans.push(Span::new(crossing(t1), crossing(t2)));
+    }
+}
+
+// infinite plane through a point, facing along its normal
+pub struct Plane
+{
+    pub point: Vector,
+    pub normal: Vector,
+}
+
+impl Plane
+{
+    pub fn new(point: Vector, normal: Vector) -> Plane
+    {
+        return Plane{ point: point, normal: Vector::normalize(&normal) };
+    }
+
+    fn crossing(&self, ray: &Ray) -> Option<Crossing>
+    {
+        let denominator = Vector::dot(&ray.direction, &self.normal);
+
+        if denominator == 0.0
+        {
+            return None;
+        }
+
+        let t = Vector::dot(&Vector::sub(&self.point, &ray.origin), &self.normal) / denominator;
+
+        // planes tile their texture coordinates once per world unit
+        let (tangent, bitangent) = orthonormal(&self.normal);
+        let local = Vector::sub(&at(ray, t), &self.point);
+        let uv = Vector::new(Vector::dot(&local, &tangent), Vector::dot(&local, &bitangent), 0.0, 0.0);
+
+        return Some(Crossing::new(t, self.normal, uv));
+    }
+}
+
+impl Shape for Plane
+{
+    fn intersect(&self, ray: &Ray) -> Option<Hit>
+    {
+        return closest(ray, &[self.crossing(ray)?]);
+    }
+
+    fn bounds(&self) -> Aabb
+    {
+        return Aabb::infinite();
+    }
+
+    fn spans(&self, ray: &Ray, spans: &mut Vec<Span>)
+    {
+        // as a solid the plane is the half-space behind its normal
+        let Some(crossing) = self.crossing(ray) else
+        {
+            if Vector::dot(&Vector::sub(&ray.origin, &self.point), &self.normal) < 0.0
+            {
+                spans.push(Span::new(Crossing::new(-f32::INFINITY, self.normal, Vector::zero()), Crossing::new(f32::INFINITY, self.normal, Vector::zero())));
+            }
+
+            return;
+        };
+
+        if Vector::dot(&ray.direction, &self.normal) < 0.0
+        {
+            spans.push(Span::new(crossing, Crossing::new(f32::INFINITY, self.normal, Vector::zero())));
+        }
+
+        else
+        {
+            spans.push(Span::new(Crossing::new(-f32::INFINITY, self.normal, Vector::zero()), crossing));
+        }
+    }
+}
+
+// entry and exit of a ray through an axis-aligned slab box, with the axis and outward sign of each face
+fn slabs(origin: &Vector, direction: &Vector, min: &Vector, max: &Vector) -> Option<[(f32, usize, f32); 2]>
+{
+    let o = origin.raw();
+    let d = direction.raw();
+    let lo = min.raw();
+    let hi = max.raw();
+
+    let mut near = (-f32::MAX, 0, 0.0);
+    let mut far = (f32::MAX, 0, 0.0);
+
+    for axis in 0..3
+    {
+        if d[axis] == 0.0
+        {
+            if o[axis] < lo[axis] || o[axis] > hi[axis]
+            {
+                return None;
+            }
+
+            continue;
+        }
+
+        let t1 = (lo[axis] - o[axis]) / d[axis];
+        let t2 = (hi[axis] - o[axis]) / d[axis];
+
+        // a ray travelling along +axis enters through the min face and leaves through the max face
+        let (enter, leave, sign) = if t1 < t2 { (t1, t2, -1.0) } else { (t2, t1, 1.0) };
+
+        if enter > near.0
+        {
+            near = (enter, axis, sign);
+        }
+
+        if leave < far.0
+        {
+            far = (leave, axis, -sign);
+        }
+
+        if near.0 > far.0
+        {
+            return None;
+        }
+    }
+
+    return Some([near, far]);
+}
+
+// face-local texture coordinates normalized over the box extent
+fn face_uv(point: &Vector, axis: usize, min: &Vector, max: &Vector) -> Vector
+{
+    let p = point.raw();
+    let lo = min.raw();
+    let hi = max.raw();
+
+    let u = (axis + 1) % 3;
+    let v = (axis + 2) % 3;
+
+    return Vector::new((p[u] - lo[u]) / (hi[u] - lo[u]), (p[v] - lo[v]) / (hi[v] - lo[v]), 0.0, 0.0);
+}
+
+fn axis_normal(axis: usize, sign: f32) -> Vector
+{
+    let mut normal = [0.0; 4];
+    normal[axis] = sign;
+
+    return Vector::new(normal[0], normal[1], normal[2], 0.0);
+}
+
+pub struct Cuboid
+{
+    pub min: Vector,
+    pub max: Vector,
+}
+
+impl Cuboid
+{
+    pub fn new(min: Vector, max: Vector) -> Cuboid
+    {
+        return Cuboid{ min: min, max: max };
+    }
+
+    fn crossings(&self, ray: &Ray) -> Option<[Crossing; 2]>
+    {
+        let faces = slabs(&ray.origin, &ray.direction, &self.min, &self.max)?;
+
+        let crossings = faces.map(|(t, axis, sign)|
+        {
+            let uv = face_uv(&at(ray, t), axis, &self.min, &self.max);
+            return Crossing::new(t, axis_normal(axis, sign), uv);
+        });
+
+        return Some(crossings);
+    }
+}
+
+impl Shape for Cuboid
+{
+    fn intersect(&self, ray: &Ray) -> Option<Hit>
+    {
+        return closest(ray, &self.crossings(ray)?);
+    }
+
+    fn bounds(&self) -> Aabb
+    {
+        return Aabb::new(self.min, self.max);
+    }
+
+    fn spans(&self, ray: &Ray, spans: &mut Vec<Span>)
+    {
+        if let Some([enter, exit]) = self.crossings(ray)
+        {
+            spans.push(Span::new(enter, exit));
+        }
+    }
+}
+
+// box spanning +-extents along three orthonormal axes around its center
+pub struct OrientedBox
+{
+    pub center: Vector,
+    pub axes: [Vector; 3],
+    pub extents: Vector,
+}
+
+impl OrientedBox
+{
+    pub fn new(center: Vector, axes: [Vector; 3], extents: Vector) -> OrientedBox
+    {
+        return OrientedBox{ center: center, axes: axes.map(|a| Vector::normalize(&a)), extents: extents };
+    }
+
+    fn to_local(&self, vec1: &Vector) -> Vector
+    {
+        return Vector::new(Vector::dot(vec1, &self.axes[0]), Vector::dot(vec1, &self.axes[1]), Vector::dot(vec1, &self.axes[2]), 0.0);
+    }
+
+    fn crossings(&self, ray: &Ray) -> Option<[Crossing; 2]>
+    {
+        let origin = self.to_local(&Vector::sub(&ray.origin, &self.center));
+        let direction = self.to_local(&ray.direction);
+
+        let max = self.extents;
+        let min = Vector::scale(&max, -1.0);
+
+        let faces = slabs(&origin, &direction, &min, &max)?;
+
+        let crossings = faces.map(|(t, axis, sign)|
+        {
+            let local = Vector::add(&origin, &Vector::scale(&direction, t));
+            let uv = face_uv(&local, axis, &min, &max);
+            return Crossing::new(t, Vector::scale(&self.axes[axis], sign), uv);
+        });
+
+        return Some(crossings);
+    }
+}
+
+impl Shape for OrientedBox
+{
+    fn intersect(&self, ray: &Ray) -> Option<Hit>
+    {
+        return closest(ray, &self.crossings(ray)?);
+    }
+
+    fn bounds(&self) -> Aabb
+    {
+        let e = self.extents;
+        let [a, b, c] = self.axes;
+
+        let half = Vector::new
+        (
+            f32::abs(a.x()) * e.x() + f32::abs(b.x()) * e.y() + f32::abs(c.x()) * e.z(),
+            f32::abs(a.y()) * e.x() + f32::abs(b.y()) * e.y() + f32::abs(c.y()) * e.z(),
+            f32::abs(a.z()) * e.x() + f32::abs(b.z()) * e.y() + f32::abs(c.z()) * e.z(),
+            0.0,
+        );
+
+        return Aabb::new(Vector::sub(&self.center, &half), Vector::add(&self.center, &half));
+    }
+
+    fn spans(&self, ray: &Ray, spans: &mut Vec<Span>)
+    {
+        if let Some([enter, exit]) = self.crossings(ray)
+        {
+            spans.push(Span::new(enter, exit));
+        }
+    }
+}
+
+pub struct Disk
+{
+    pub center: Vector,
+    pub normal: Vector,
+    pub radius: f32,
+}
+
+impl Disk
+{
+    pub fn new(center: Vector, normal: Vector, radius: f32) -> Disk
+    {
+        return Disk{ center: center, normal: Vector::normalize(&normal), radius: radius };
+    }
+
+    fn crossing(&self, ray: &Ray) -> Option<Crossing>
+    {
+        let denominator = Vector::dot(&ray.direction, &self.normal);
+
+        if denominator == 0.0
+        {
+            return None;
+        }
+
+        let t = Vector::dot(&Vector::sub(&self.center, &ray.origin), &self.normal) / denominator;
+        let local = Vector::sub(&at(ray, t), &self.center);
+
+        let distance2 = Vector::length2(&local);
+
+        if distance2 > self.radius * self.radius
+        {
+            return None;
+        }
+
+        let (tangent, bitangent) = orthonormal(&self.normal);
+        let angle = (f32::atan2(Vector::dot(&local, &bitangent), Vector::dot(&local, &tangent)) + PI) / (2.0 * PI);
+        let uv = Vector::new(angle, f32::sqrt(distance2) / self.radius, 0.0, 0.0);
+
+        return Some(Crossing::new(t, self.normal, uv));
+    }
+}
+
+impl Shape for Disk
+{
+    fn intersect(&self, ray: &Ray) -> Option<Hit>
+    {
+        return closest(ray, &[self.crossing(ray)?]);
+    }
+
+    fn spans(&self, ray: &Ray, spans: &mut Vec<Span>)
+    {
+        // a disk has no volume, so it only contributes an empty span where it is crossed
+        if let Some(crossing) = self.crossing(ray)
+        {
+            spans.push(Span::new(crossing, crossing));
+        }
+    }
+
+    fn bounds(&self) -> Aabb
+    {
+        let n = self.normal;
+
+        // a tilted disk reaches radius * sin(angle to each axis)
+        let half = Vector::new
+        (
+            self.radius * f32::sqrt(f32::max(0.0, 1.0 - n.x() * n.x())),
+            self.radius * f32::sqrt(f32::max(0.0, 1.0 - n.y() * n.y())),
+            self.radius * f32::sqrt(f32::max(0.0, 1.0 - n.z() * n.z())),
+            0.0,
+        );
+
+        return Aabb::new(Vector::sub(&self.center, &half), Vector::add(&self.center, &half));
+    }
+}
+
+// capped cylinder around the y axis, centered on the origin
+pub struct Cylinder
+{
+    pub radius: f32,
+    pub height: f32,
+}
+
+impl Cylinder
+{
+    pub fn new(radius: f32, height: f32) -> Cylinder
+    {
+        return Cylinder{ radius: radius, height: height };
+    }
+
+    fn crossings(&self, ray: &Ray, crossings: &mut Vec<Crossing>)
+    {
+        let o = ray.origin;
+        let d = ray.direction;
+
+        let half = 0.5 * self.height;
+
+        let a = d.x() * d.x() + d.z() * d.z();
+        let b = 2.0 * (o.x() * d.x() + o.z() * d.z());
+        let c = o.x() * o.x() + o.z() * o.z() - self.radius * self.radius;
+
+        let mut roots = [0.0; 2];
+        let count = polynomial::quadratic([c as f64, b as f64, a as f64], &mut roots);
+
+        for root in roots.iter().take(count)
+        {
+            let t = *root as f32;
+            let p = at(ray, t);
+
+            if f32::abs(p.y()) <= half
+            {
+                let normal = Vector::new(p.x() / self.radius, 0.0, p.z() / self.radius, 0.0);
+                let uv = Vector::new(azimuth(p.x(), p.z()), (p.y() + half) / self.height, 0.0, 0.0);
+                crossings.push(Crossing::new(t, normal, uv));
+            }
+        }
+
+        if d.y() != 0.0
+        {
+            for sign in [-1.0, 1.0]
+            {
+                let t = (sign * half - o.y()) / d.y();
+                let p = at(ray, t);
+
+                let distance2 = p.x() * p.x() + p.z() * p.z();
+
+                if distance2 <= self.radius * self.radius
+                {
+                    let uv = Vector::new(azimuth(p.x(), p.z()), f32::sqrt(distance2) / self.radius, 0.0, 0.0);
+                    crossings.push(Crossing::new(t, Vector::new(0.0, sign, 0.0, 0.0), uv));
+                }
+            }
+        }
+    }
+}
+
+impl Shape for Cylinder
+{
+    fn intersect(&self, ray: &Ray) -> Option<Hit>
+    {
+        let mut crossings = Vec::with_capacity(4);
+        self.crossings(ray, &mut crossings);
+
+        return closest(ray, &crossings);
+    }
+
+    fn spans(&self, ray: &Ray, spans: &mut Vec<Span>)
+    {
+        let mut crossings = Vec::with_capacity(4);
+        self.crossings(ray, &mut crossings);
+
+        pair(&mut crossings, spans);
+    }
+
+    fn bounds(&self) -> Aabb
+    {
+        let half = Vector::new(self.radius, 0.5 * self.height, self.radius, 0.0);
+        return Aabb::new(Vector::scale(&half, -1.0), half);
+    }
+}
+
+// capped cone around the y axis with its base on the origin and its apex at the given height
+pub struct Cone
+{
+    pub radius: f32,
+    pub height: f32,
+}
+
+impl Cone
+{
+    pub fn new(radius: f32, height: f32) -> Cone
+    {
+        return Cone{ radius: radius, height: height };
+    }
+
+    fn crossings(&self, ray: &Ray, crossings: &mut Vec<Crossing>)
+    {
+        let o = ray.origin;
+        let d = ray.direction;
+
+        let h = self.height;
+        let k = self.radius / self.height;
+        let k2 = k * k;
+
+        // x^2 + z^2 = k^2 (h - y)^2
+        let a = d.x() * d.x() + d.z() * d.z() - k2 * d.y() * d.y();
+        let b = 2.0 * (o.x() * d.x() + o.z() * d.z() + k2 * (h - o.y()) * d.y());
+        let c = o.x() * o.x() + o.z() * o.z() - k2 * (h - o.y()) * (h - o.y());
+
+        let mut roots = [0.0; 2];
+        let count = polynomial::quadratic([c as f64, b as f64, a as f64], &mut roots);
+
+        for root in roots.iter().take(count)
+        {
+            let t = *root as f32;
+            let p = at(ray, t);
+
+            // discard the mirrored nappe above the apex
+            if p.y() >= 0.0 && p.y() <= h
+            {
+                let normal = Vector::normalize(&Vector::new(p.x(), k2 * (h - p.y()), p.z(), 0.0));
+                let uv = Vector::new(azimuth(p.x(), p.z()), p.y() / h, 0.0, 0.0);
+                crossings.push(Crossing::new(t, normal, uv));
+            }
+        }
+
+        if d.y() != 0.0
+        {
+            let t = -o.y() / d.y();
+            let p = at(ray, t);
+
+            let distance2 = p.x() * p.x() + p.z() * p.z();
+
+            if distance2 <= self.radius * self.radius
+            {
+                let uv = Vector::new(azimuth(p.x(), p.z()), f32::sqrt(distance2) / self.radius, 0.0, 0.0);
+                crossings.push(Crossing::new(t, Vector::new(0.0, -1.0, 0.0, 0.0), uv));
+            }
+        }
+    }
+}
+
+impl Shape for Cone
+{
+    fn intersect(&self, ray: &Ray) -> Option<Hit>
+    {
+        let mut crossings = Vec::with_capacity(3);
+        self.crossings(ray, &mut crossings);
+
+        return closest(ray, &crossings);
+    }
+
+    fn spans(&self, ray: &Ray, spans: &mut Vec<Span>)
+    {
+        let mut crossings = Vec::with_capacity(3);
+        self.crossings(ray, &mut crossings);
+
+        pair(&mut crossings, spans);
+    }
+
+    fn bounds(&self) -> Aabb
+    {
+        return Aabb::new(Vector::new(-self.radius, 0.0, -self.radius, 1.0), Vector::new(self.radius, self.height, self.radius, 1.0));
+    }
+}
+
+// torus lying in the xz plane around the y axis
+pub struct Torus
+{
+    pub major: f32,
+    pub minor: f32,
+}
+
+impl Torus
+{
+    pub fn new(major: f32, minor: f32) -> Torus
+    {
+        return Torus{ major: major, minor: minor };
+    }
+
+    fn crossings(&self, ray: &Ray, crossings: &mut Vec<Crossing>)
+    {
+        // restart the ray at the bounding box to keep the quartic well conditioned
+        let Some((near, _)) = Aabb::intersect(&self.bounds(), ray) else
+        {
+            return;
+        };
+
+        let o = at(ray, near);
+        let d = ray.direction;
+
+        let (ox, oy, oz) = (o.x() as f64, o.y() as f64, o.z() as f64);
+        let (dx, dy, dz) = (d.x() as f64, d.y() as f64, d.z() as f64);
+
+        let r2 = (self.major * self.major) as f64;
+        let s2 = (self.minor * self.minor) as f64;
+
+        // (|p|^2 + R^2 - r^2)^2 = 4 R^2 (x^2 + z^2)
+        let dd = dx * dx + dy * dy + dz * dz;
+        let f = ox * dx + oy * dy + oz * dz;
+        let k = ox * ox + oy * oy + oz * oz + r2 - s2;
+
+        let coefficients =
+        [
+            k * k - 4.0 * r2 * (ox * ox + oz * oz),
+            4.0 * f * k - 8.0 * r2 * (ox * dx + oz * dz),
+            4.0 * f * f + 2.0 * dd * k - 4.0 * r2 * (dx * dx + dz * dz),
+            4.0 * dd * f,
+            dd * dd,
+        ];
+
+        let mut roots = [0.0; 4];
+        let count = polynomial::quartic(coefficients, &mut roots);
+
+        for root in roots.iter().take(count)
+        {
+            let s = polynomial::polish(&coefficients, *root, 2);
+            let t = near + s as f32;
+            let p = at(ray, t);
+
+            // the normal points away from the nearest point on the center ring
+            let ring = f32::sqrt(p.x() * p.x() + p.z() * p.z());
+            let center = if ring > 0.0 { Vector::new(p.x() * self.major / ring, 0.0, p.z() * self.major / ring, 0.0) } else { Vector::zero() };
+            let normal = Vector::normalize(&Vector::new(p.x() - center.x(), p.y(), p.z() - center.z(), 0.0));
+
+            let uv = Vector::new(azimuth(p.x(), p.z()), (f32::atan2(p.y(), ring - self.major) + PI) / (2.0 * PI), 0.0, 0.0);
+            crossings.push(Crossing::new(t, normal, uv));
+        }
+
+        crossings.sort_by(|a, b| a.distance.total_cmp(&b.distance));
+    }
+}
+
+impl Shape for Torus
+{
+    fn intersect(&self, ray: &Ray) -> Option<Hit>
+    {
+        let mut crossings = Vec::with_capacity(4);
+        self.crossings(ray, &mut crossings);
+
+        return closest(ray, &crossings);
+    }
+
+    fn spans(&self, ray: &Ray, spans: &mut Vec<Span>)
+    {
+        let mut crossings = Vec::with_capacity(4);
+        self.crossings(ray, &mut crossings);
+
+        pair(&mut crossings, spans);
+    }
+
+    fn bounds(&self) -> Aabb
+    {
+        let outer = self.major + self.minor;
+        return Aabb::new(Vector::new(-outer, -self.minor, -outer, 1.0), Vector::new(outer, self.minor, outer, 1.0));
+    }
+}
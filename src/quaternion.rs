@@ -0,0 +1,189 @@
+// (c) 2025 Connor J. Link. All Rights Reserved.
+// Luma - quaternion.rs
+
+use crate::vector::*;
+use crate::matrix::*;
+
+// unit quaternion rotation with the vector part in x, y, z and the scalar part in w
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Quaternion
+{
+    pub x: f32,
+    pub y: f32,
+    pub z: f32,
+    pub w: f32,
+}
+
+impl Quaternion
+{
+    pub fn new(x: f32, y: f32, z: f32, w: f32) -> Quaternion
+    {
+        return Quaternion{ x: x, y: y, z: z, w: w };
+    }
+
+    pub fn identity() -> Quaternion
+    {
+        return Quaternion{ x: 0.0, y: 0.0, z: 0.0, w: 1.0 };
+    }
+
+    // right-handed rotation about the axis, matching Matrix::rotation
+    pub fn axis_angle(axis: &Vector, angle: f32) -> Quaternion
+    {
+        let k = Vector::normalize(axis);
+        let s = f32::sin(0.5 * angle);
+
+        return Quaternion{ x: k.x() * s, y: k.y() * s, z: k.z() * s, w: f32::cos(0.5 * angle) };
+    }
+
+    // returns the unit axis and the angle in radians, with an arbitrary axis for the identity
+    pub fn to_axis_angle(q: &Quaternion) -> (Vector, f32)
+    {
+        let q = Quaternion::normalize(q);
+        let s = f32::sqrt(f32::max(1.0 - q.w * q.w, 0.0));
+
+        if s < 1e-6
+        {
+            return (Vector::new(1.0, 0.0, 0.0, 0.0), 0.0);
+        }
+
+        return (Vector::new(q.x / s, q.y / s, q.z / s, 0.0), 2.0 * f32::acos(f32::clamp(q.w, -1.0, 1.0)));
+    }
+
+    // applied about x, then y, then z, matching Matrix::rotation_euler
+    pub fn euler(angles: &Vector) -> Quaternion
+    {
+        let x = Quaternion::axis_angle(&Vector::new(1.0, 0.0, 0.0, 0.0), angles.x());
+        let y = Quaternion::axis_angle(&Vector::new(0.0, 1.0, 0.0, 0.0), angles.y());
+        let z = Quaternion::axis_angle(&Vector::new(0.0, 0.0, 1.0, 0.0), angles.z());
+
+        return Quaternion::mul(&Quaternion::mul(&x, &y), &z);
+    }
+
+    // inverse of euler, with the y angle limited to plus or minus a quarter turn
+    pub fn to_euler(q: &Quaternion) -> Vector
+    {
+        let m = Matrix::from_quaternion(q);
+
+        // in the row-vector matrix of rx * ry * rz, row 0 column 2 holds -sin(y)
+        let sin_y = f32::clamp(-m.get(0, 2), -1.0, 1.0);
+        let y = f32::asin(sin_y);
+
+        if f32::abs(sin_y) < 0.9999
+        {
+            let x = f32::atan2(m.get(1, 2), m.get(2, 2));
+            let z = f32::atan2(m.get(0, 1), m.get(0, 0));
+
+            return Vector::new(x, y, z, 0.0);
+        }
+
+        // gimbal lock: x and z rotate about the same axis, so fold everything into x
+        let x = f32::atan2(-m.get(2, 1), m.get(1, 1));
+        return Vector::new(x, y, 0.0, 0.0);
+    }
+
+    // rotation q1 followed by q2, the same order as Matrix::mul
+    pub fn mul(q1: &Quaternion, q2: &Quaternion) -> Quaternion
+    {
+        // hamilton product q2 * q1
+        return Quaternion
+        {
+            x: q2.w * q1.x + q2.x * q1.w + q2.y * q1.z - q2.z * q1.y,
+            y: q2.w * q1.y - q2.x * q1.z + q2.y * q1.w + q2.z * q1.x,
+            z: q2.w * q1.z + q2.x * q1.y - q2.y * q1.x + q2.z * q1.w,
+            w: q2.w * q1.w - q2.x * q1.x - q2.y * q1.y - q2.z * q1.z,
+        };
+    }
+
+    pub fn conjugate(q: &Quaternion) -> Quaternion
+    {
+        return Quaternion{ x: -q.x, y: -q.y, z: -q.z, w: q.w };
+    }
+
+    pub fn inverse(q: &Quaternion) -> Quaternion
+    {
+        let length2 = Quaternion::dot(q, q);
+
+        if length2 == 0.0
+        {
+            return Quaternion::identity();
+        }
+
+        let conjugate = Quaternion::conjugate(q);
+        return Quaternion{ x: conjugate.x / length2, y: conjugate.y / length2, z: conjugate.z / length2, w: conjugate.w / length2 };
+    }
+
+    pub fn dot(q1: &Quaternion, q2: &Quaternion) -> f32
+    {
+        return q1.x * q2.x + q1.y * q2.y + q1.z * q2.z + q1.w * q2.w;
+    }
+
+    pub fn normalize(q: &Quaternion) -> Quaternion
+    {
+        let length = f32::sqrt(Quaternion::dot(q, q));
+
+        if length == 0.0
+        {
+            return Quaternion::identity();
+        }
+
+        return Quaternion{ x: q.x / length, y: q.y / length, z: q.z / length, w: q.w / length };
+    }
+
+    pub fn rotate(q: &Quaternion, vec1: &Vector) -> Vector
+    {
+        // v' = v + 2w(u x v) + 2u x (u x v), with u the vector part
+        let u = Vector::new(q.x, q.y, q.z, 0.0);
+        let v = Vector::new(vec1.x(), vec1.y(), vec1.z(), 0.0);
+
+        let t = Vector::cross(&u, &v) * 2.0;
+        let rotated = v + t * q.w + Vector::cross(&u, &t);
+
+        return Vector::new(rotated.x(), rotated.y(), rotated.z(), vec1.w());
+    }
+
+    // spherical interpolation along the shorter arc, falling back to a normalized lerp when nearly parallel
+    pub fn slerp(q1: &Quaternion, q2: &Quaternion, t: f32) -> Quaternion
+    {
+        let mut end = *q2;
+        let mut cos_theta = Quaternion::dot(q1, q2);
+
+        if cos_theta < 0.0
+        {
+            end = Quaternion{ x: -q2.x, y: -q2.y, z: -q2.z, w: -q2.w };
+            cos_theta = -cos_theta;
+        }
+
+        let (weight1, weight2) = if cos_theta > 0.9995
+        {
+            (1.0 - t, t)
+        }
+
+        else
+        {
+            let theta = f32::acos(cos_theta);
+            let sin_theta = f32::sin(theta);
+
+            (f32::sin((1.0 - t) * theta) / sin_theta, f32::sin(t * theta) / sin_theta)
+        };
+
+        let blended = Quaternion
+        {
+            x: q1.x * weight1 + end.x * weight2,
+            y: q1.y * weight1 + end.y * weight2,
+            z: q1.z * weight1 + end.z * weight2,
+            w: q1.w * weight1 + end.w * weight2,
+        };
+
+        return Quaternion::normalize(&blended);
+    }
+
+    pub fn to_matrix(q: &Quaternion) -> Matrix
+    {
+        return Matrix::from_quaternion(q);
+    }
+
+    pub fn from_matrix(mat1: &Matrix) -> Quaternion
+    {
+        return Matrix::to_quaternion(mat1);
+    }
+}
@@ -22,4 +22,61 @@ impl Ray
     {
         return Ray{ origin: Vector::zero(), direction: Vector::zero() };
     }
-}
\ No newline at end of file
+
+    pub fn at(&self, t: f32) -> Vector
+    {
+        return Vector::add(&self.origin, &Vector::scale(&self.direction, t));
+    }
+
+    // per-axis bound on the rounding error of at(t), allowing t itself a few ulps of error
+    pub fn error(&self, t: f32) -> Vector
+    {
+        let origin = self.origin.raw();
+        let direction = self.direction.raw();
+
+        let bound = |axis: usize| gamma(1) * f32::abs(origin[axis]) + gamma(7) * f32::abs(t * direction[axis]);
+        return Vector::new(bound(0), bound(1), bound(2), 0.0);
+    }
+
+    // starts a ray leaving a surface, pushed along the normal just past the error box of the hit point, so that
+    // it can't hit the surface it left without the fixed offset that leaks light at small scales and leaves acne
+    // at large ones
+    pub fn spawn(position: &Vector, error: &Vector, normal: &Vector, direction: &Vector) -> Ray
+    {
+        let p = position.raw();
+        let n = normal.raw();
+
+        // distance along the normal to the face of the error box
+        let offset = f32::abs(n[0]) * error.x() + f32::abs(n[1]) * error.y() + f32::abs(n[2]) * error.z();
+        let side = if Vector::dot(normal, direction) < 0.0 { -offset } else { offset };
+
+        let mut origin = [0.0; 4];
+
+        for axis in 0..3
+        {
+            origin[axis] = p[axis] + side * n[axis];
+
+            // the addition itself rounds, so step one more float away from the surface
+            if side * n[axis] > 0.0
+            {
+                origin[axis] = f32::next_up(origin[axis]);
+            }
+
+            else if side * n[axis] < 0.0
+            {
+                origin[axis] = f32::next_down(origin[axis]);
+            }
+        }
+
+        origin[3] = position.w();
+
+        return Ray::new(Vector::from_raw(origin), *direction);
+    }
+}
+
+// higham's bound on the relative error accumulated over n rounded floating-point operations
+pub fn gamma(n: u32) -> f32
+{
+    let epsilon = 0.5 * f32::EPSILON;
+    return (n as f32 * epsilon) / (1.0 - n as f32 * epsilon);
+}
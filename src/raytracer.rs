@@ -1,9 +1,33 @@
 // (c) 2025 Connor J. Link. All Rights Reserved.
 // Luma - renderer.rs
 
+use std::rc::Rc;
+
 use crate::vector::*;
+use crate::types::*;
+use crate::matrix::*;
+use crate::quaternion::*;
 use crate::camera::*;
+use crate::input::*;
 use crate::ray::*;
+use crate::shape::*;
+use crate::primitive::*;
+use crate::instance::*;
+use crate::csg::*;
+use crate::sdf::*;
+use crate::bounds::*;
+use crate::animation::*;
+use crate::scene::*;
+use crate::path::*;
+use crate::sampling::*;
+use crate::preview::*;
+use crate::color::*;
+use crate::world::*;
+use crate::spectrum::*;
+use crate::packet;
+use crate::image;
+
+use std::f32::consts::PI;
 
 use rand::Rng;
 
@@ -13,52 +37,231 @@ struct Pixel
     depth: f32,
 }
 
-struct Material
+// what a path carries from one bounce to the next: the ray it follows, the throughput gathered so far and how many
+// more surfaces it may reach
+struct PathState
+{
+    ray: Ray,
+    contribution: Vector,
+    bounces: u32,
+}
+
+impl PathState
+{
+    fn new(ray: Ray, bounces: u32) -> PathState
+    {
+        return PathState{ ray: ray, contribution: Vector::broadcast(1.0), bounces: bounces };
+    }
+}
+
+#[derive(Clone)]
+#[derive(Copy)]
+pub struct Material
+{
+    pub diffuse: Vector,
+    pub specular: Vector,
+    pub emissive: Vector,
+    pub metallic: f32,
+    pub roughness: f32,
+
+    // chance that a ray passes through the surface instead of bouncing off, tinted by the diffuse colour
+    pub transmission: f32,
+
+    // index of refraction at the sodium d line (587.6nm)
+    pub ior: f32,
+
+    // abbe number, where lower means a stronger spread of the index over wavelength and 0 turns dispersion off
+    pub abbe: f32,
+}
+
+impl Material
 {
-    diffuse: Vector,
-    specular: Vector,
-    emissive: Vector,
-    metallic: f32,
-    roughness: f32,
+    // cauchy's equation n = a + b / lambda^2, fitted to the index and abbe number at the standard fraunhofer lines
+    pub fn ior_at(&self, lambda: f32) -> f32
+    {
+        if self.abbe <= 0.0
+        {
+            return self.ior;
+        }
+
+        const D: f32 = 0.5876;
+        const F: f32 = 0.4861;
+        const C: f32 = 0.6563;
+
+        let micrometres = 0.001 * lambda;
+
+        let b = (self.ior - 1.0) / (self.abbe * (1.0 / (F * F) - 1.0 / (C * C)));
+        let a = self.ior - b / (D * D);
+
+        return a + b / (micrometres * micrometres);
+    }
 }
 
-struct Sphere
+struct Object
 {
-    position: Vector,
-    radius: f32,
+    instance: Instance,
     material: Material,
+
+    // where the object sits in the world, and its rotation and scale about that point. the instance holds the two
+    // combined relative to the floating origin, so it has to be placed again whenever the origin moves
+    position: WorldPosition,
+    transform: Matrix,
+
+    // placement the object was created with, which animation falls back to for any component it doesn't key
+    rest: Rest,
+}
+
+#[derive(Clone)]
+#[derive(Copy)]
+struct Rest
+{
+    position: WorldPosition,
+    orientation: Quaternion,
+    scale: Vector,
+}
+
+impl Object
+{
+    fn new(shape: Rc<dyn Shape>, position: WorldPosition, transform: Matrix, material: Material, origin: &FloatingOrigin) -> Object
+    {
+        // any translation in the transform is folded into the position so the rest pose is plain trs
+        let (translation, orientation, scale) = Matrix::decompose(&transform);
+        let position = position.offset(&translation);
+        let transform = Matrix::trs(&Vector::zero(), &orientation, &scale);
+
+        let rest = Rest{ position: position, orientation: orientation, scale: scale };
+
+        let mut object = Object{ instance: Instance::new(shape, Matrix::identity()), material: material, position: position, transform: transform, rest: rest };
+        object.place(origin);
+
+        return object;
+    }
+
+    fn place(&mut self, origin: &FloatingOrigin)
+    {
+        let translation = Matrix::translation(&origin.to_local(&self.position));
+        self.instance.set_transform(Matrix::mul(&self.transform, &translation));
+    }
 }
 
 struct Intersection
 {
     color: Vector,
-    position: Vector,
-    normal: Vector,
+    position: Point,
+    normal: Normal,
     distance: f32,
     exit: f32,
-    object: *const Sphere,
+
+    // rounding error bound on the position
+    error: Vector,
+
+    // index into the scene object list
+    object: Option<usize>,
+}
+
+// what a ray query found, with the object identified by its index in the scene
+#[derive(Clone)]
+#[derive(Copy)]
+pub struct Query
+{
+    pub object: usize,
+    pub position: Point,
+    pub normal: Normal,
+    pub distance: f32,
+    pub material: Material,
+}
+
+// an in-flight camera move between two viewpoints
+struct Transition
+{
+    from: Bookmark,
+    to: Bookmark,
+    elapsed: f32,
+    duration: f32,
+}
+
+// a recorded path being replayed, with the frame times it has produced so far
+struct Playback
+{
+    path: CameraPath,
+    rate: PlaybackRate,
+    time: f32,
+    timings: Timings,
 }
 
 struct Scene
 {
-    objects: Vec<Sphere>,
+    objects: Vec<Object>,
     sun: Vector,
 }
 
 pub struct Raytracer
 {
     frametime: f32,
+
+    // full frames accumulated since the last reset, plus one, and every frame rendered since then including previews
     frame_count: f32,
+    frames: u32,
+
+    // scene time in seconds used to evaluate the animation
+    time: f32,
+    animation: Animation,
 
     accumulate: bool,
     accumulated_data: Vec<Vector>,
     framebuffer: Vec<Vector>,
 
+    // per-pixel luminance statistics that steer samples towards noisy pixels
+    estimates: Vec<Estimate>,
+    sampling: AdaptiveSampling,
+    converged: bool,
+    show_heatmap: bool,
+
+    // working space the lighting is computed in, and how it is turned into display values for the surface
+    output: OutputTransform,
+
+    // traces four wavelengths per path instead of rgb, converting back to rgb as each sample lands
+    spectral: bool,
+    wavelengths: Option<Wavelengths>,
+
+    // coarse frames traced while the view is changing, refined back to full resolution once it settles
+    preview: Preview,
+    changed: bool,
+    stride: usize,
+    pixel_cost: f32,
+
     camera: Camera,
+    input: InputController,
+
+    // the camera and instances live relative to this origin, so precision doesn't fall off away from the world origin
+    origin: FloatingOrigin,
+    commands: Vec<CameraCommand>,
+
+    // window size, optionally overridden by a fixed resolution, scaled down to the traced resolution
+    output_size: [usize; 2],
+    fixed_resolution: Option<[usize; 2]>,
+    render_scale: f32,
 
-    closest: Intersection,
 
     scene: Scene,
+    selected: Option<usize>,
+
+    // indexed by slot, so the number keys always reach the same viewpoint, with gaps for unused slots
+    bookmarks: Vec<Option<Bookmark>>,
+
+    // the scene file last loaded, which stored bookmarks are written back to
+    scene_path: Option<String>,
+    transition: Option<Transition>,
+
+    // timestamps and steps come from the wall clock, since frametime only covers rendering and misses the rest of the
+    // frame
+    recording: Option<CameraPath>,
+    recording_start: std::time::Instant,
+    playback: Option<Playback>,
+
+    // wall-clock time between the last two updates
+    last_update: std::time::Instant,
+    delta: f32,
 }
 
 impl Raytracer
@@ -69,64 +272,200 @@ impl Raytracer
         {
             frametime: 0.0,
             frame_count: 1.0, // avoid division by zero
+            frames: 0,
+            time: 0.0,
+            animation: Animation::new(),
             accumulate: true,
             accumulated_data: vec![Vector::zero(); width * height],
             framebuffer: vec![Vector::zero(); width * height],
+            estimates: vec![Estimate::new(); width * height],
+            sampling: AdaptiveSampling::new(),
+            converged: false,
+            show_heatmap: false,
+            output: OutputTransform::new(),
+            spectral: false,
+            wavelengths: None,
+            preview: Preview::new(),
+            changed: false,
+            stride: 1,
+            pixel_cost: 0.0,
             camera: Camera::new(90.0, 0.1, 1000.0, width, height),
-            closest: Intersection{ color: Vector::zero(), position: Vector::zero(), normal: Vector::zero(), distance: 0.0, exit: 0.0, object: std::ptr::null() },
+            input: InputController::new(),
+            origin: FloatingOrigin::new(),
+            commands: Vec::new(),
+            output_size: [width, height],
+            fixed_resolution: None,
+            render_scale: 1.0,
             scene: Scene{ objects: Vec::new(), sun: Vector::zero() },
+            selected: None,
+            bookmarks: Vec::new(),
+            scene_path: None,
+            transition: None,
+            recording: None,
+            recording_start: std::time::Instant::now(),
+            playback: None,
+            last_update: std::time::Instant::now(),
+            delta: 0.0,
         };
 
-        renderer.scene = Scene
-        {
-            objects: vec![
-                Sphere
-                {
-                    position: Vector::new(0.0, 0.0, 5.0, 1.0),
-                    radius: 1.0,
-                    material: Material
-                    {
-                        diffuse: Vector::new(1.0, 0.0, 0.0, 1.0),
-                        specular: Vector::new(1.0, 0.3, 0.3, 1.0),
-                        emissive: Vector::zero(),
-                        metallic: 0.9,
-                        roughness: 0.5,
-                    },
-                },
-                Sphere
-                {
-                    position: Vector::new(3.0, 0.0, 5.0, 1.0),
-                    radius: 1.5,
-                    material: Material
-                    {
-                        diffuse: Vector::new(0.0, 1.0, 0.0, 1.0),
-                        specular: Vector::new(0.3, 1.0, 0.3, 1.0),
-                        emissive: Vector::zero(),
-                        metallic: 0.7,
-                        roughness: 0.0,
-                    },
-                },
-                Sphere
-                {
-                    position: Vector::new(0.0, 1003.0, 0.0, 1.0),
-                    radius: 1000.0,
-                    material: Material
-                    {
-                        diffuse: Vector::new(0.85, 0.85, 1.0, 1.0),
-                        specular: Vector::new(0.4, 0.4, 1.0, 1.0),
-                        emissive: Vector::zero(),
-                        metallic: 0.0,
-                        roughness: 0.0,
-                    },
-                },
-            ],
+        renderer.scene.sun = Vector::new(10.0, -10.0, -10.0, 1.0);
+
+        // the spheres are all instances of the same unit sphere
+        let sphere: Rc<dyn Shape> = Rc::new(Sphere::unit());
+
+        renderer.add_instance_at
+        (
+            sphere.clone(),
+            WorldPosition::new(0.0, 0.0, 5.0),
+            Matrix::scaling(&Vector::broadcast(1.0)),
+            Material
+            {
+                diffuse: Vector::new(1.0, 0.0, 0.0, 1.0),
+                specular: Vector::new(1.0, 0.3, 0.3, 1.0),
+                emissive: Vector::zero(),
+                metallic: 0.9,
+                roughness: 0.5,
+                transmission: 0.0,
+                ior: 1.5,
+                abbe: 0.0,
+            },
+        );
+
+        renderer.add_instance_at
+        (
+            sphere.clone(),
+            WorldPosition::new(3.0, 0.0, 5.0),
+            Matrix::scaling(&Vector::broadcast(1.5)),
+            Material
+            {
+                diffuse: Vector::new(0.0, 1.0, 0.0, 1.0),
+                specular: Vector::new(0.3, 1.0, 0.3, 1.0),
+                emissive: Vector::zero(),
+                metallic: 0.7,
+                roughness: 0.0,
+                transmission: 0.0,
+                ior: 1.5,
+                abbe: 0.0,
+            },
+        );
+
+        // flint glass, dispersive enough to split colours visibly in spectral mode
+        renderer.add_instance_at
+        (
+            sphere,
+            WorldPosition::new(-2.5, 0.0, 4.0),
+            Matrix::scaling(&Vector::broadcast(1.0)),
+            Material
+            {
+                diffuse: Vector::new(0.95, 0.95, 0.95, 1.0),
+                specular: Vector::new(1.0, 1.0, 1.0, 1.0),
+                emissive: Vector::zero(),
+                metallic: 0.0,
+                roughness: 0.0,
+                transmission: 1.0,
+                ior: 1.62,
+                abbe: 36.4,
+            },
+        );
+
+        // the floor, facing up towards the camera
+        renderer.add_instance_at
+        (
+            Rc::new(Plane::new(Vector::zero(), Vector::new(0.0, -1.0, 0.0, 0.0))),
+            WorldPosition::new(0.0, 3.0, 0.0),
+            Matrix::identity(),
+            Material
+            {
+                diffuse: Vector::new(0.85, 0.85, 1.0, 1.0),
+                specular: Vector::new(0.4, 0.4, 1.0, 1.0),
+                emissive: Vector::zero(),
+                metallic: 0.0,
+                roughness: 0.0,
+                transmission: 0.0,
+                ior: 1.5,
+                abbe: 0.0,
+            },
+        );
 
-            sun: Vector::new(10.0, -10.0, -10.0, 1.0),
+        let matte = |diffuse: Vector|
+        {
+            return Material
+            {
+                diffuse: diffuse,
+                specular: diffuse,
+                emissive: Vector::zero(),
+                metallic: 0.0,
+                roughness: 0.6,
+                transmission: 0.0,
+                ior: 1.5,
+                abbe: 0.0,
+            };
         };
 
+        // a row of the other analytic primitives standing on the floor behind the spheres, where up is -y
+        let x_axis = Vector::new(1.0, 0.0, 0.0, 0.0);
+
+        let half = Vector::broadcast(0.6);
+        let cuboid = Rc::new(Cuboid::new(-half, half));
+        renderer.add_instance_at(cuboid, WorldPosition::new(-6.0, 2.4, 7.5), Matrix::identity(), matte(Vector::new(0.9, 0.6, 0.2, 1.0)));
+
+        let axes = [Vector::new(1.0, 0.0, 1.0, 0.0), Vector::new(0.0, 1.0, 0.0, 0.0), Vector::new(-1.0, 0.0, 1.0, 0.0)];
+        let oriented = Rc::new(OrientedBox::new(Vector::zero(), axes, Vector::new(0.5, 0.7, 0.5, 0.0)));
+        renderer.add_instance_at(oriented, WorldPosition::new(-3.6, 2.3, 7.5), Matrix::identity(), matte(Vector::new(0.8, 0.8, 0.3, 1.0)));
+
+        let cylinder = Rc::new(Cylinder::new(0.6, 1.4));
+        renderer.add_instance_at(cylinder, WorldPosition::new(-1.2, 2.3, 7.5), Matrix::identity(), matte(Vector::new(0.3, 0.8, 0.5, 1.0)));
+
+        // the cone's apex points along +y, so turn it over to stand on its base
+        let cone = Rc::new(Cone::new(0.7, 1.5));
+        renderer.add_instance_at(cone, WorldPosition::new(1.2, 3.0, 7.5), Matrix::rotation(&x_axis, PI), matte(Vector::new(0.3, 0.5, 0.9, 1.0)));
+
+        // stood upright to face the camera
+        let torus = Rc::new(Torus::new(0.6, 0.25));
+        renderer.add_instance_at(torus, WorldPosition::new(3.6, 2.15, 7.5), Matrix::rotation(&x_axis, 0.5 * PI), matte(Vector::new(0.7, 0.3, 0.8, 1.0)));
+
+        let disk = Rc::new(Disk::new(Vector::zero(), Vector::new(0.0, 0.0, -1.0, 0.0), 0.8));
+        renderer.add_instance_at(disk, WorldPosition::new(6.0, 2.2, 7.5), Matrix::identity(), matte(Vector::new(0.9, 0.3, 0.3, 1.0)));
+
+        // a block with a spherical dent scooped out of the face toward the camera
+        let block = Rc::new(Cuboid::new(-Vector::broadcast(0.7), Vector::broadcast(0.7)));
+        let scoop = Rc::new(Sphere::new(Vector::new(0.0, 0.0, -0.7, 1.0), 0.55));
+        let dented = Rc::new(Csg::difference(block, scoop));
+        renderer.add_instance_at(dented, WorldPosition::new(-4.8, 2.3, 4.5), Matrix::identity(), matte(Vector::new(0.8, 0.8, 0.8, 1.0)));
+
+        // two spheres melted together by a smooth union and sphere traced, the smaller one stacked on top
+        let base = Sdf::Sphere{ radius: 0.6 };
+        let head = Sdf::Translate{ offset: Vector::new(0.0, -0.8, 0.0, 0.0), child: Box::new(Sdf::Sphere{ radius: 0.4 }) };
+        let blob = Rc::new(DistanceField::new(Sdf::SmoothUnion{ k: 0.3, left: Box::new(base), right: Box::new(head) }));
+        renderer.add_instance_at(blob, WorldPosition::new(4.8, 2.4, 4.5), Matrix::identity(), matte(Vector::new(0.4, 0.7, 0.9, 1.0)));
+
         return renderer;
     }
 
+    // places a shape in the scene with an object-to-world transform, returning its index for materials, animation
+    // and picking. any translation in the transform is taken as a world position
+    pub fn add_instance(&mut self, shape: Rc<dyn Shape>, transform: Matrix, material: Material) -> usize
+    {
+        return self.add_instance_at(shape, WorldPosition::origin(), transform, material);
+    }
+
+    // the same, with the placement given in double precision so shapes far from the world origin stay exact
+    pub fn add_instance_at(&mut self, shape: Rc<dyn Shape>, position: WorldPosition, transform: Matrix, material: Material) -> usize
+    {
+        let object = Object::new(shape, position, transform, material, &self.origin);
+        self.scene.objects.push(object);
+
+        self.reset_accumulation();
+
+        return self.scene.objects.len() - 1;
+    }
+
+    pub fn object_count(&self) -> usize
+    {
+        return self.scene.objects.len();
+    }
+
+    // linear radiance in the working space, before the output transform
     pub fn framebuffer(&self) -> &Vec<Vector>
     {
         return &self.framebuffer;
@@ -137,9 +476,67 @@ impl Raytracer
         return self.frametime;
     }
 
+    // camera position in the world, as opposed to relative to the floating origin
     pub fn position(&self) -> Vector
     {
-        return self.camera.position();
+        return self.world_position().to_vector();
+    }
+
+    pub fn world_position(&self) -> WorldPosition
+    {
+        return self.origin.to_world(&self.camera.position());
+    }
+
+    pub fn camera_relative(&self) -> bool
+    {
+        return self.origin.enabled;
+    }
+
+    pub fn set_camera_relative(&mut self, enabled: bool)
+    {
+        if enabled == self.origin.enabled
+        {
+            return;
+        }
+
+        self.origin.enabled = enabled;
+
+        if enabled
+        {
+            self.rebase();
+        }
+
+        else
+        {
+            let shift = self.origin.reset();
+            self.shift(&shift);
+        }
+    }
+
+    // bookmarks, paths and animation keys are stored in world space, so they come through here on the way in
+    fn to_local(&self, position: &WorldPosition) -> Vector
+    {
+        return self.origin.to_local(position);
+    }
+
+    // moves the origin to the camera once it has strayed far enough for precision to suffer
+    fn rebase(&mut self)
+    {
+        if let Some(shift) = self.origin.rebase(&self.camera.position())
+        {
+            self.shift(&shift);
+        }
+    }
+
+    // everything in local space moves against the origin so the world stays put
+    fn shift(&mut self, shift: &Vector)
+    {
+        self.camera.rebase(shift);
+
+        for object in &mut self.scene.objects
+        {
+            object.place(&self.origin);
+        }
     }
 
     pub fn rotation(&self) -> Vector
@@ -147,18 +544,29 @@ impl Raytracer
         return self.camera.direction();
     }
 
+    // the framebuffer through the output transform, as 8-bit display values
     pub fn bitmap(&self) -> Vec<u8>
     {
         let mut bitmap = vec![0; self.framebuffer.len() * 4];
 
-        for (i, pixel) in self.framebuffer.iter().enumerate()
+        for (i, color) in self.framebuffer.iter().enumerate()
         {
             let index = i * 4;
 
-            bitmap[index + 0] = (pixel.x() * 255.0) as u8;
-            bitmap[index + 1] = (pixel.y() * 255.0) as u8;
-            bitmap[index + 2] = (pixel.z() * 255.0) as u8;
-            bitmap[index + 3] = (pixel.w() * 255.0) as u8;
+            let pixel = if self.show_heatmap
+            {
+                heatmap(self.estimates[i].count, self.sampling.max_samples)
+            }
+
+            else
+            {
+                self.output.apply(color)
+            };
+
+            bitmap[index + 0] = (pixel.x() * 255.0 + 0.5) as u8;
+            bitmap[index + 1] = (pixel.y() * 255.0 + 0.5) as u8;
+            bitmap[index + 2] = (pixel.z() * 255.0 + 0.5) as u8;
+            bitmap[index + 3] = (pixel.w() * 255.0 + 0.5) as u8;
         }
 
         return bitmap;
@@ -169,250 +577,1262 @@ impl Raytracer
         return [self.camera.width(), self.camera.height()];
     }
 
-    fn jitter(vec1: &Vector, noise: f32) -> Vector
+    pub fn render_scale(&self) -> f32
     {
-        let mut rng = rand::rng();
+        return self.render_scale;
+    }
 
-        return Vector::new
-        (
-            vec1.x() + rng.random::<f32>() * noise,
-            vec1.y() + rng.random::<f32>() * noise,
-            vec1.z() + rng.random::<f32>() * noise,
-            vec1.w(),
-        );
+    pub fn set_render_scale(&mut self, scale: f32)
+    {
+        self.render_scale = f32::clamp(scale, 0.05, 4.0);
+        self.reallocate();
     }
 
-    fn fresnel(intersection: &Intersection, ray: &Ray) -> f32
+    // follows the window size again when given none
+    pub fn set_resolution(&mut self, resolution: Option<[usize; 2]>)
     {
-        let direction = Vector::scale(&ray.direction, -1.0);
+        self.fixed_resolution = resolution;
+        self.reallocate();
+    }
 
-        let mut metallic: f32 = 0.0;
+    pub fn resize(&mut self, width: usize, height: usize)
+    {
+        self.output_size = [width, height];
+        self.reallocate();
+    }
 
-        if !intersection.object.is_null()
-        {   
-            unsafe
-            {
-                metallic = (*intersection.object).material.metallic;
-            }
-        }
+    fn reallocate(&mut self)
+    {
+        let [base_width, base_height] = self.fixed_resolution.unwrap_or(self.output_size);
 
-        // fresnel's law
-        let cos_incident = Vector::dot(&intersection.normal, &direction);
-        let coefficient = metallic;
-        let sin_theta_squared = coefficient * coefficient * (1.0 - cos_incident * cos_incident);
+        let width = usize::max((base_width as f32 * self.render_scale).round() as usize, 1);
+        let height = usize::max((base_height as f32 * self.render_scale).round() as usize, 1);
 
-        if sin_theta_squared > 1.0
+        if [width, height] == self.size()
         {
-            // total internal reflection
-            return 1.0;
+            return;
         }
 
-        let cos_theta = f32::sqrt(1.0 - sin_theta_squared);
-
-        // schlick approximation
-        let r0 = (1.0 - coefficient) / (1.0 + coefficient);
-        let r0_squared = r0 * r0;
-
-        let partial = 1.0 - cos_theta;
-        let power = partial * partial * partial * partial * partial;
+        self.camera.resize(width, height);
 
-        let r = r0_squared + (1.0 - r0_squared) * power;
+        self.framebuffer.clear();
+        self.framebuffer.resize(width * height, Vector::zero());
 
-        let fresnel = 1.0 - f32::clamp(r, 0.0, 1.0);
-        return fresnel;
+        self.reset_accumulation();
     }
 
-    fn direct(_intersection: &Intersection) -> Vector
+    pub fn time(&self) -> f32
     {
-        return Vector::zero();
+        return self.time;
     }
 
-    fn indirect(_intersection: &Intersection) -> Vector
+    pub fn animation(&self) -> &Animation
     {
-        return Vector::zero();
+        return &self.animation;
     }
 
-    fn reflect(intersection: &Intersection, ray: &Ray) -> Ray
+    pub fn animation_mut(&mut self) -> &mut Animation
     {
-        const EPSILON: f32 = 0.001;
-
-        // ensure the reflection Ray doesn't re-hit the same object due to floating-point inaccuracy
-        let extruded = Vector::scale(&intersection.normal, EPSILON);
-        let position = Vector::add(&intersection.position, &extruded);
+        return &mut self.animation;
+    }
 
-        // then reflect across the normal
-        let direction = Vector::reflect(&ray.direction, &intersection.normal);
-        return Ray::new(position, direction);
+    pub fn set_time(&mut self, time: f32)
+    {
+        self.time = time;
+        self.animate();
     }
 
-    fn miss() -> Intersection
+    fn animate(&mut self)
     {
-        return Intersection
+        let time = self.time;
+        let tracks = &self.animation.camera;
+
+        let position = tracks.position.sample(time).map_or(self.camera.position(), |position| self.to_local(&position));
+        let yaw = tracks.yaw.sample(time).unwrap_or(self.camera.yaw());
+        let pitch = tracks.pitch.sample(time).unwrap_or(self.camera.pitch());
+
+        if let Some(orientation) = tracks.orientation.sample(time)
         {
-            color: Vector::zero(),
-            position: Vector::zero(),
-            normal: Vector::zero(),
-            distance: f32::MAX,
-            exit: f32::MAX,
-            object: std::ptr::null(),
-        };
-    }
+            self.camera.set_transform(position, &orientation);
+        }
 
-    // TODO: refraction
+        else if !tracks.position.is_empty() || !tracks.yaw.is_empty() || !tracks.pitch.is_empty()
+        {
+            self.camera.set_pose(position, yaw, pitch);
+        }
 
-    fn trace(&mut self, ray: &Ray) -> Intersection
-    {
-        let mut distance = f32::MAX;
+        if let Some(fov) = tracks.fov.sample(time)
+        {
+            self.camera.set_fov(fov);
+        }
 
-        let mut intersection = Self::miss();
+        self.rebase();
 
-        for object in &self.scene.objects 
+        if let Some(sun) = self.animation.sun.sample(time)
         {
-            let difference = Vector::sub(&ray.origin, &object.position);
+            self.scene.sun = sun;
+        }
 
-            let a = Vector::dot(&ray.direction, &ray.direction);
-            let b = 2.0 * Vector::dot(&ray.direction, &difference);
-            let c = Vector::dot(&difference, &difference) - object.radius * object.radius;
+        let origin = self.origin;
 
-            let d = b * b - 4.0 * a * c;
+        for tracks in &self.animation.objects
+        {
+            let Some(object) = self.scene.objects.get_mut(tracks.object) else
+            {
+                continue;
+            };
 
-            if d > 0.0 
+            if !tracks.translation.is_empty() || !tracks.rotation.is_empty() || !tracks.scale.is_empty() || !tracks.orientation.is_empty()
             {
-                let t1 = (-b - f32::sqrt(d)) / (2.0 * a);
-                let t2 = (-b + f32::sqrt(d)) / (2.0 * a);
+                // unkeyed components keep the object's rest placement. the translation is a world position, kept out
+                // of the transform so it can be placed relative to the origin
+                let rest = object.rest;
 
-                if t1 > 0.0
-                {
-                    let progress = Vector::scale(&ray.direction, t1);
-                    let hit = Vector::add(&ray.origin, &progress);
+                let position = tracks.translation.sample(time).unwrap_or(rest.position);
+                let scale = tracks.scale.sample(time).unwrap_or(rest.scale);
 
-                    let toward = Vector::sub(&hit, &object.position);
-                    let normal = Vector::normalize(&toward);
+                let orientation = match tracks.orientation.sample(time)
+                {
+                    Some(orientation) => orientation,
+                    None => tracks.rotation.sample(time).map_or(rest.orientation, |rotation| Quaternion::euler(&rotation)),
+                };
 
-                    if t1 < distance 
-                    {
-                        distance = t1;
-                        intersection = Intersection
-                        {
-                            color: object.material.diffuse,
-                            position: hit,
-                            normal: normal,
-                            distance: t1,
-                            exit: t2,
-                            object: object as *const Sphere,
-                        };
-                    }
-                }
+                object.transform = Matrix::trs(&Vector::zero(), &orientation, &scale);
+                object.position = position;
+                object.place(&origin);
             }
-        }
 
-        if distance == f32::MAX
-        {
-            return Self::miss();
-        }
+            let material = &mut object.material;
 
-        return intersection;
-    }
+            material.diffuse = tracks.diffuse.sample(time).unwrap_or(material.diffuse);
+            material.specular = tracks.specular.sample(time).unwrap_or(material.specular);
+            material.emissive = tracks.emissive.sample(time).unwrap_or(material.emissive);
+            material.metallic = tracks.metallic.sample(time).unwrap_or(material.metallic);
+            material.roughness = tracks.roughness.sample(time).unwrap_or(material.roughness);
+        }
 
-    fn gamma_correct(color: Vector, gamma: f32) -> Vector
-    {
-        return Vector::new
-        (
-            f32::powf(color.x(), 1.0 / gamma),
-            f32::powf(color.y(), 1.0 / gamma),
-            f32::powf(color.z(), 1.0 / gamma),
-            color.w(),
-        );
+        // any animated property invalidates the accumulated samples
+        self.reset_accumulation();
     }
 
-    fn tonemap(color: Vector) -> Vector
+    pub fn render_sequence(&mut self, sequence: &Sequence) -> std::io::Result<()>
     {
-        const EXPOSURE: f32 = 1.0;
+        // every output frame is a full render, so the interactive preview stays off
+        let enabled = std::mem::replace(&mut self.preview.enabled, false);
+        let result = self.render_frames(sequence);
+        self.preview.enabled = enabled;
 
-        let mapped = Vector::new
-        (
-            1.0 - f32::exp(-color.x() * EXPOSURE),
-            1.0 - f32::exp(-color.y() * EXPOSURE),
-            1.0 - f32::exp(-color.z() * EXPOSURE),
-            color.w(),
-        );
-
-        return mapped;
+        return result;
     }
 
-    fn shade(&mut self, x: usize, y: usize, bounces: u32, contribution: &mut Vector, ray: &mut Ray) -> Pixel
+    fn render_frames(&mut self, sequence: &Sequence) -> std::io::Result<()>
     {
-        if bounces == 0
-        {
-            return Pixel
-            {
-                color: Vector::broadcast(1.0),
-                depth: f32::MAX,
-            };
-        }
-
-        let depth = f32::MAX;
+        let [width, height] = self.size();
 
-        let intersection = self.trace(&ray);
-
-        if intersection.object.is_null()
+        for frame in sequence.start..=sequence.end
         {
-            // no intersection, so cast to sky
+            self.set_time(sequence.time(frame));
 
-            let top_sky_color = Vector::new(0.529, 0.808, 0.922, 1.0);
-            let bottom_sky_color = Vector::new(0.106, 0.275, 0.711, 1.0);
+            for _ in 0..sequence.samples.max(1)
+            {
+                self.render(sequence.bounces);
 
-            let clamped = f32::clamp(ray.direction.y(), -1.0, 1.0);
-            let adjusted = (clamped + 1.0) * 0.5;
+                if self.converged
+                {
+                    break;
+                }
+            }
 
-            let sky = Vector::lerp(&bottom_sky_color, &top_sky_color, adjusted);
+            let filename = sequence.filename(frame);
+            image::write_ppm(&filename, width, height, &self.bitmap())?;
 
-            *contribution = Vector::mul( contribution, &sky);
+            log::info!("rendered frame {} to {}", frame, filename);
         }
 
-        else
-        {
-            let direction_jittered = Vector::normalize(&Self::jitter(&ray.direction, 0.01));
-            *ray = Ray::new(ray.origin, direction_jittered);
-
-            let object = unsafe { &*intersection.object };
-            let material= &object.material;
+        return Ok(());
+    }
 
-            let metallic = material.metallic;
+    fn reset_accumulation(&mut self)
+    {
+        let width = self.camera.width();
+        let height = self.camera.height();
 
-            let inverted = Vector::scale(&direction_jittered, -1.0);
-            let cos_theta = Vector::dot(&intersection.normal, &inverted);
+        self.frame_count = 1.0;
+        self.frames = 0;
 
-            let fresnel = Self::fresnel(&intersection, &ray);
+        self.accumulated_data.clear();
+        self.accumulated_data.resize(width * height, Vector::zero());
 
-            let base_color = &material.diffuse;
-            
-            let specular = self.shade(x, y, bounces - 1, contribution, ray);
-            let specular_color = specular.color;
+        self.estimates.clear();
+        self.estimates.resize(width * height, Estimate::new());
+        self.converged = false;
+        self.changed = true;
 
-            let specular_blend = Vector::lerp(&specular_color, &base_color, metallic);
+        self.camera.moved = false;
+    }
 
-            let diffuse_contribution = Vector::scale(base_color, (1.0 - metallic) * cos_theta.max(0.0));
-            let specular_contribution = Vector::scale(&specular_blend, fresnel);
+    fn jitter(vec1: &Vector, noise: f32) -> Vector
+    {
+        let mut rng = rand::rng();
+
+        return Vector::new
+        (
+            vec1.x() + rng.random::<f32>() * noise,
+            vec1.y() + rng.random::<f32>() * noise,
+            vec1.z() + rng.random::<f32>() * noise,
+            vec1.w(),
+        );
+    }
+
+    fn fresnel(&self, intersection: &Intersection, ray: &Ray) -> f32
+    {
+        let direction = -ray.direction;
+
+        let metallic = intersection.object.map_or(0.0, |index| self.scene.objects[index].material.metallic);
+
+        // fresnel's law
+        let cos_incident = intersection.normal.dot(&Direction::from(direction));
+        let coefficient = metallic;
+        let sin_theta_squared = coefficient * coefficient * (1.0 - cos_incident * cos_incident);
+
+        if sin_theta_squared > 1.0
+        {
+            // total internal reflection
+            return 1.0;
+        }
+
+        let cos_theta = f32::sqrt(1.0 - sin_theta_squared);
+
+        // schlick approximation
+        let r0 = (1.0 - coefficient) / (1.0 + coefficient);
+        let r0_squared = r0 * r0;
+
+        let partial = 1.0 - cos_theta;
+        let power = partial * partial * partial * partial * partial;
+
+        let r = r0_squared + (1.0 - r0_squared) * power;
+
+        let fresnel = 1.0 - f32::clamp(r, 0.0, 1.0);
+        return fresnel;
+    }
+
+    // cosine-weighted light reaching the hit from the sun, taken as infinitely far off in the direction of scene.sun,
+    // and zero where something blocks it or no sun is set
+    fn sunlight(&self, intersection: &Intersection, incoming: &Vector) -> f32
+    {
+        let sun = self.scene.sun;
+        let toward = Vector::new(sun.x(), sun.y(), sun.z(), 0.0);
+
+        if Vector::length2(&toward) == 0.0
+        {
+            return 0.0;
+        }
+
+        let toward = Vector::normalize(&toward);
+
+        // light the side the ray arrived on, whichever way the shape's normal faces
+        let mut normal: Vector = intersection.normal.into();
+
+        if Vector::dot(&normal, incoming) > 0.0
+        {
+            normal = -normal;
+        }
+
+        let cos_theta = Vector::dot(&normal, &toward);
+
+        if cos_theta <= 0.0
+        {
+            return 0.0;
+        }
+
+        let shadow = Ray::spawn(&intersection.position.into(), &intersection.error, &normal, &toward);
+
+        if self.trace(&shadow).object.is_some()
+        {
+            return 0.0;
+        }
+
+        return cos_theta;
+    }
+
+    fn reflect(intersection: &Intersection, ray: &Ray) -> Ray
+    {
+        let direction = Direction::from(ray.direction).reflect(&intersection.normal);
+
+        // start just outside the error bounds of the hit so the reflection can't re-hit the same surface
+        return Ray::spawn(&intersection.position.into(), &intersection.error, &intersection.normal.into(), &direction.into());
+    }
+
+    fn miss() -> Intersection
+    {
+        return Intersection
+        {
+            color: Vector::zero(),
+            position: Point::origin(),
+            normal: Normal::new(0.0, 1.0, 0.0),
+            distance: f32::MAX,
+            exit: f32::MAX,
+            error: Vector::zero(),
+            object: None,
+        };
+    }
+
+    fn intersection(&self, index: usize, hit: &Hit) -> Intersection
+    {
+        return Intersection
+        {
+            color: self.scene.objects[index].material.diffuse,
+            position: Point::from(hit.position),
+            normal: Normal::from(hit.normal),
+            distance: hit.distance,
+            exit: hit.exit,
+            error: hit.error,
+            object: Some(index),
+        };
+    }
+
+    fn trace(&self, ray: &Ray) -> Intersection
+    {
+        let mut distance = f32::MAX;
+
+        let mut intersection = Self::miss();
+
+        for (index, object) in self.scene.objects.iter().enumerate()
+        {
+            let Some(hit) = object.instance.intersect(ray) else
+            {
+                continue;
+            };
+
+            if hit.distance < distance 
+            {
+                distance = hit.distance;
+                intersection = self.intersection(index, &hit);
+            }
+        }
+
+        if distance == f32::MAX
+        {
+            return Self::miss();
+        }
+
+        return intersection;
+    }
+
+    // nearest hits of a run of coherent primary rays, traced in packets of eight
+    fn trace_packets(&self, rays: &[Ray], intersections: &mut Vec<Intersection>)
+    {
+        let boxes: Vec<Aabb> = self.scene.objects.iter().map(|object| object.instance.bounds()).collect();
+
+        let mut hits = Vec::with_capacity(rays.len());
+        packet::trace_shapes::<8, _>(rays, &boxes, |index, ray| self.scene.objects[index].instance.intersect(ray), &mut hits);
+
+        intersections.clear();
+
+        for hit in &hits
+        {
+            intersections.push(match hit
+            {
+                Some((index, hit)) => self.intersection(*index as usize, hit),
+                None => Self::miss(),
+            });
+        }
+    }
+
+    // a scene colour, authored as linear rec.709, as carried along the current path: moved into the working space, or
+    // uplifted to its wavelengths in spectral mode
+    fn color(&self, rgb: &Vector) -> Vector
+    {
+        return match &self.wavelengths
+        {
+            Some(wavelengths) => wavelengths.uplift(rgb),
+            None => ColorSpace::convert(rgb, ColorSpace::LinearSrgb, self.output.working),
+        };
+    }
+
+    // continues the path through a transparent surface, bending it by the index at the hero wavelength
+    fn transmit(&mut self, path: &mut PathState, intersection: &Intersection, material: &Material) -> Pixel
+    {
+        let ior = match self.wavelengths.as_mut()
+        {
+            // each wavelength bends differently, so only the hero can follow this path
+            Some(wavelengths) if material.abbe > 0.0 =>
+            {
+                wavelengths.terminate_secondary();
+                material.ior_at(wavelengths.hero())
+            }
+
+            _ => material.ior,
+        };
+
+        let direction = Direction::from(path.ray.direction).normalize();
+        let entering = intersection.normal.dot(&direction) < 0.0;
+
+        let (normal, eta) = if entering { (intersection.normal, 1.0 / ior) } else { (-intersection.normal, ior) };
+
+        // total internal reflection keeps the path inside
+        let next = direction.refract(&normal, eta).unwrap_or_else(|| direction.reflect(&normal));
+
+        path.ray = Ray::spawn(&intersection.position.into(), &intersection.error, &intersection.normal.into(), &next.into());
+
+        path.bounces -= 1;
+        let transmitted = self.shade(path);
+
+        // the surface colour filters whatever is seen through it
+        path.contribution = transmitted.color * self.color(&material.diffuse);
+
+        return Pixel
+        {
+            color: path.contribution,
+            depth: f32::MAX,
+        };
+    }
+
+    fn shade(&mut self, path: &mut PathState) -> Pixel
+    {
+        if path.bounces == 0
+        {
+            return Pixel
+            {
+                color: Vector::broadcast(1.0),
+                depth: f32::MAX,
+            };
+        }
+
+        let intersection = self.trace(&path.ray);
+
+        return self.shade_hit(path, &intersection);
+    }
+
+    // shades a path at a surface it has already been traced to
+    fn shade_hit(&mut self, path: &mut PathState, intersection: &Intersection) -> Pixel
+    {
+        let depth = f32::MAX;
+
+        let Some(object) = intersection.object else
+        {
+            // no intersection, so cast to sky
+
+            // picked on screen, so stored srgb encoded
+            let top_sky_color = decode_srgb(&Vector::new(0.529, 0.808, 0.922, 1.0));
+            let bottom_sky_color = decode_srgb(&Vector::new(0.106, 0.275, 0.711, 1.0));
+
+            let clamped = f32::clamp(path.ray.direction.y(), -1.0, 1.0);
+            let adjusted = (clamped + 1.0) * 0.5;
+
+            let sky = Vector::lerp(&bottom_sky_color, &top_sky_color, adjusted);
+
+            path.contribution = path.contribution * self.color(&sky);
+
+            return Pixel
+            {
+                color: path.contribution,
+                depth: depth,
+            };
+        };
+
+        let direction_jittered = Vector::normalize(&Self::jitter(&path.ray.direction, 0.01));
+        path.ray = Ray::new(path.ray.origin, direction_jittered);
+
+        let material = self.scene.objects[object].material;
+
+        if material.transmission > 0.0 && rand::rng().random::<f32>() < material.transmission
+        {
+            return self.transmit(path, intersection, &material);
+        }
+
+        let metallic = material.metallic;
+
+        // a little ambient stands in for the sky so that faces turned from the sun and shadows aren't black
+        const AMBIENT: f32 = 0.15;
+        let light = AMBIENT + (1.0 - AMBIENT) * self.sunlight(intersection, &direction_jittered);
+
+        let fresnel = self.fresnel(intersection, &path.ray);
+
+        let base_color = self.color(&material.diffuse);
+
+        // continue along the reflection, leaving from the hit rather than the previous origin
+        path.ray = Self::reflect(intersection, &path.ray);
+        path.ray.direction = Self::jitter(&path.ray.direction, 0.5 * material.roughness);
+
+        path.bounces -= 1;
+        let specular = self.shade(path);
+        let specular_color = specular.color;
+
+        let specular_blend = Vector::lerp(&specular_color, &base_color, metallic);
+
+        let diffuse_contribution = base_color * ((1.0 - metallic) * light);
+        let specular_contribution = specular_blend * fresnel;
+
+        let total_contribution = diffuse_contribution + specular_contribution;
+
+        path.contribution = path.contribution * total_contribution;
+
+        return Pixel 
+        {
+            color: path.contribution,
+            depth: depth,
+        };
+    }
+
+    pub fn input_mut(&mut self) -> &mut InputController
+    {
+        return &mut self.input;
+    }
+
+    pub fn camera(&self) -> &Camera
+    {
+        return &self.camera;
+    }
+
+    pub fn camera_mut(&mut self) -> &mut Camera
+    {
+        return &mut self.camera;
+    }
+
+    // nearest object along a world-space ray
+    pub fn cast(&self, ray: &Ray) -> Option<Query>
+    {
+        let intersection = self.trace(ray);
+        let object = intersection.object?;
+
+        return Some(Query
+        {
+            object: object,
+            position: intersection.position,
+            normal: intersection.normal,
+            distance: intersection.distance,
+            material: self.scene.objects[object].material,
+        });
+    }
+
+    // object under a pixel of the traced image
+    pub fn pick(&self, x: usize, y: usize) -> Option<Query>
+    {
+        let [width, height] = self.size();
+
+        if x >= width || y >= height
+        {
+            return None;
+        }
+
+        let ray = self.camera.rays()[y * width + x];
+
+        if Vector::length2(&ray.direction) == 0.0
+        {
+            return None;
+        }
+
+        return self.cast(&ray);
+    }
+
+    // object under a point given as a fraction of the image width and height
+    pub fn pick_normalized(&self, u: f32, v: f32) -> Option<Query>
+    {
+        if !(0.0..1.0).contains(&u) || !(0.0..1.0).contains(&v)
+        {
+            return None;
+        }
+
+        let [width, height] = self.size();
+        return self.pick((u * width as f32) as usize, (v * height as f32) as usize);
+    }
+
+    pub fn material(&self, object: usize) -> Option<&Material>
+    {
+        return self.scene.objects.get(object).map(|o| &o.material);
+    }
+
+    pub fn set_material(&mut self, object: usize, material: Material)
+    {
+        if let Some(target) = self.scene.objects.get_mut(object)
+        {
+            target.material = material;
+            self.reset_accumulation();
+        }
+    }
+
+    pub fn spectral(&self) -> bool
+    {
+        return self.spectral;
+    }
+
+    pub fn set_spectral(&mut self, spectral: bool)
+    {
+        if spectral != self.spectral
+        {
+            self.spectral = spectral;
+            self.reset_accumulation();
+        }
+    }
+
+    pub fn output(&self) -> &OutputTransform
+    {
+        return &self.output;
+    }
+
+    // bitmap() is handed to egui, which reads its bytes as srgb whatever the surface is, so the default encodes.
+    // linear output is for consumers that encode themselves, such as an srgb texture written directly
+    pub fn set_output(&mut self, output: OutputTransform)
+    {
+        let working = self.output.working;
+        self.output = output;
+
+        // the accumulated radiance only depends on the working space, the rest applies at display time
+        if output.working != working
+        {
+            self.reset_accumulation();
+        }
+    }
+
+    pub fn set_working_space(&mut self, working: ColorSpace)
+    {
+        if working != self.output.working
+        {
+            self.output.working = working;
+            self.reset_accumulation();
+        }
+    }
+
+    pub fn selected(&self) -> Option<usize>
+    {
+        return self.selected;
+    }
+
+    pub fn select(&mut self, object: Option<usize>)
+    {
+        self.selected = object.filter(|index| *index < self.scene.objects.len());
+    }
+
+    pub fn frame_selected(&mut self)
+    {
+        let Some(index) = self.selected else
+        {
+            return;
+        };
+
+        let bounds = self.scene.objects[index].instance.bounds();
+
+        if !bounds.is_finite()
+        {
+            return;
+        }
+
+        let sphere = BoundingSphere::from_aabb(&bounds);
+        self.camera.frame(sphere.center, sphere.radius);
+    }
+
+    pub fn bookmarks(&self) -> &Vec<Option<Bookmark>>
+    {
+        return &self.bookmarks;
+    }
+
+    // snapshot of the current viewpoint
+    pub fn bookmark(&self, name: &str) -> Bookmark
+    {
+        return Bookmark::new(name, self.world_position(), self.camera.yaw(), self.camera.pitch(), self.camera.fov(), self.camera.focus());
+    }
+
+    // stores the current viewpoint, replacing any bookmark with the same name
+    pub fn add_bookmark(&mut self, name: &str)
+    {
+        let bookmark = self.bookmark(name);
+
+        match self.bookmarks.iter_mut().flatten().find(|b| b.name == name)
+        {
+            Some(existing) => *existing = bookmark,
+            None => self.bookmarks.push(Some(bookmark)),
+        }
+    }
+
+    // stores the current viewpoint into a slot, growing the list with empty slots as needed
+    pub fn store_bookmark(&mut self, slot: usize, name: &str)
+    {
+        if slot >= self.bookmarks.len()
+        {
+            self.bookmarks.resize(slot + 1, None);
+        }
+
+        self.bookmarks[slot] = Some(self.bookmark(name));
+    }
+
+    // empties the slot, leaving the later slots where they are
+    pub fn remove_bookmark(&mut self, index: usize)
+    {
+        if let Some(slot) = self.bookmarks.get_mut(index)
+        {
+            *slot = None;
+        }
+
+        while self.bookmarks.last().is_some_and(|slot| slot.is_none())
+        {
+            self.bookmarks.pop();
+        }
+    }
+
+    fn apply_bookmark(&mut self, bookmark: &Bookmark)
+    {
+        self.camera.set_pose(self.to_local(&bookmark.position), bookmark.yaw, bookmark.pitch);
+        self.camera.set_focus(bookmark.focus);
+
+        if bookmark.fov != self.camera.fov()
+        {
+            self.camera.set_fov(bookmark.fov);
+        }
+    }
+
+    // moves to a bookmark, easing over the given number of seconds or cutting straight to it
+    pub fn goto_bookmark(&mut self, index: usize, duration: f32)
+    {
+        let Some(to) = self.bookmarks.get(index).cloned().flatten() else
+        {
+            return;
+        };
+
+        if duration <= 0.0
+        {
+            self.transition = None;
+            self.apply_bookmark(&to);
+            return;
+        }
+
+        self.transition = Some(Transition{ from: self.bookmark(""), to: to, elapsed: 0.0, duration: duration });
+    }
+
+    fn step_transition(&mut self)
+    {
+        let Some(transition) = &mut self.transition else
+        {
+            return;
+        };
+
+        transition.elapsed += self.delta;
+
+        let t = f32::min(transition.elapsed / transition.duration, 1.0);
+        let eased = t * t * (3.0 - 2.0 * t);
+        let bookmark = Bookmark::lerp(&transition.from, &transition.to, eased);
+
+        if t >= 1.0
+        {
+            self.transition = None;
+        }
+
+        self.apply_bookmark(&bookmark);
+    }
+
+    pub fn load_scene(&mut self, path: &str) -> std::io::Result<()>
+    {
+        // remembered even if the file doesn't exist yet, so storing a bookmark creates it
+        self.scene_path = Some(path.to_owned());
+
+        let file = SceneFile::load(path)?;
+
+        if let Some(sun) = file.sun
+        {
+            self.scene.sun = sun;
+        }
+
+        self.bookmarks = file.bookmarks;
+        self.goto_bookmark(0, 0.0);
+        self.reset_accumulation();
+
+        return Ok(());
+    }
+
+    pub fn save_scene(&self, path: &str) -> std::io::Result<()>
+    {
+        let file = SceneFile{ sun: Some(self.scene.sun), bookmarks: self.bookmarks.clone() };
+        return file.save(path);
+    }
+
+    pub fn sampling(&self) -> &AdaptiveSampling
+    {
+        return &self.sampling;
+    }
+
+    pub fn sampling_mut(&mut self) -> &mut AdaptiveSampling
+    {
+        return &mut self.sampling;
+    }
+
+    // true once no pixel needs more samples, so headless renders can stop early
+    pub fn converged(&self) -> bool
+    {
+        return self.converged;
+    }
+
+    pub fn show_heatmap(&self) -> bool
+    {
+        return self.show_heatmap;
+    }
+
+    pub fn set_show_heatmap(&mut self, show: bool)
+    {
+        self.show_heatmap = show;
+    }
+
+    // times packet tracing of the primary rays, first through the simd sphere kernels with each object stood in for
+    // by the sphere inscribed in its bounds, then against the real objects the way render traces them
+    pub fn benchmark_packets(&self, iterations: u32) -> String
+    {
+        let spheres: Vec<Sphere> = self.scene.objects.iter().map(|object|
+        {
+            let bounds = object.instance.bounds();
+            Sphere::new(bounds.center(), 0.5 * bounds.extent().x())
+        }).collect();
 
-            let total_contribution = Vector::add(&diffuse_contribution, &specular_contribution);
+        let kernels = packet::benchmark(self.camera.rays(), &spheres, iterations);
 
-            *contribution = Vector::mul(contribution, &total_contribution);
+        let rays = self.camera.rays();
+        let iterations = iterations.max(1);
 
-            ray.direction = Self::reflect(&intersection, &ray).direction;
-            ray.direction = Self::jitter(&ray.direction, 0.5 * material.roughness);
+        let now = std::time::Instant::now();
+
+        for _ in 0..iterations
+        {
+            for ray in rays
+            {
+                std::hint::black_box(self.trace(ray));
+            }
         }
 
-        return Pixel 
+        let single = now.elapsed().as_secs_f32() / iterations as f32;
+
+        let mut intersections = Vec::new();
+        let now = std::time::Instant::now();
+
+        for _ in 0..iterations
         {
-            color: *contribution,
-            depth: depth,
+            self.trace_packets(rays, &mut intersections);
+        }
+
+        let packets = now.elapsed().as_secs_f32() / iterations as f32;
+        let agree = intersections.iter().zip(rays).all(|(intersection, ray)| intersection.object == self.trace(ray).object);
+
+        return format!("{}; scene: single {:.3} ms, 8-wide {:.3} ms ({:.2}x{})",
+            kernels, 1000.0 * single, 1000.0 * packets, single / packets, if agree { "" } else { ", mismatch" });
+    }
+
+    pub fn start_recording(&mut self)
+    {
+        self.recording = Some(CameraPath::new());
+        self.recording_start = std::time::Instant::now();
+    }
+
+    pub fn stop_recording(&mut self) -> Option<CameraPath>
+    {
+        return self.recording.take();
+    }
+
+    pub fn is_recording(&self) -> bool
+    {
+        return self.recording.is_some();
+    }
+
+    fn apply_sample(&mut self, sample: &PathSample)
+    {
+        self.camera.set_transform(self.to_local(&sample.position), &sample.orientation);
+
+        if sample.fov != self.camera.fov()
+        {
+            self.camera.set_fov(sample.fov);
+        }
+    }
+
+    pub fn play(&mut self, path: CameraPath, rate: PlaybackRate)
+    {
+        let Some(start) = path.sample(0.0) else
+        {
+            return;
+        };
+
+        self.transition = None;
+        self.apply_sample(&start);
+
+        self.playback = Some(Playback{ path: path, rate: rate, time: 0.0, timings: Timings::new() });
+    }
+
+    // ends playback early, returning the frame times measured so far
+    pub fn stop_playback(&mut self) -> Option<Timings>
+    {
+        return self.playback.take().map(|playback| playback.timings);
+    }
+
+    pub fn is_playing(&self) -> bool
+    {
+        return self.playback.is_some();
+    }
+
+    fn step_playback(&mut self)
+    {
+        let frametime = self.frametime;
+        let delta = self.delta;
+        let rendered = self.frames;
+
+        // preview frames are rendered but leave nothing in the accumulation buffer
+        let accumulated = self.frame_count as u32 - 1;
+
+        let Some(playback) = &mut self.playback else
+        {
+            return;
+        };
+
+        // the first update happens before anything was rendered for this path
+        if rendered > 0
+        {
+            playback.timings.frames.push(frametime);
+        }
+
+        let step = match playback.rate
+        {
+            PlaybackRate::Realtime => delta,
+            PlaybackRate::Fixed{ fps } => 1.0 / fps,
+            PlaybackRate::Samples{ fps, samples } => if accumulated >= samples.max(1) { 1.0 / fps } else { 0.0 },
+        };
+
+        if step == 0.0 || rendered == 0
+        {
+            return;
+        }
+
+        playback.time += step;
+
+        if playback.time > playback.path.duration()
+        {
+            if let Some(timings) = self.stop_playback()
+            {
+                log::info!("path playback finished: {}", timings.summary());
+            }
+
+            return;
+        }
+
+        let Some(sample) = playback.path.sample(playback.time) else
+        {
+            return;
+        };
+
+        self.apply_sample(&sample);
+    }
+
+    // renders a recorded path through the image sequence output, with the frame range derived from its duration
+    pub fn render_path(&mut self, path: &CameraPath, fps: f32, samples: u32, bounces: u32, pattern: &str) -> std::io::Result<()>
+    {
+        let end = f32::ceil(path.duration() * fps) as u32;
+
+        let mut sequence = Sequence::new(0, end, fps, pattern);
+        sequence.samples = samples;
+        sequence.bounces = bounces;
+
+        let saved = std::mem::replace(&mut self.animation.camera, path.to_tracks());
+        let result = self.render_sequence(&sequence);
+        self.animation.camera = saved;
+
+        return result;
+    }
+
+    fn egui_key(key: Key) -> egui::Key
+    {
+        return match key
+        {
+            Key::W => egui::Key::W,
+            Key::A => egui::Key::A,
+            Key::S => egui::Key::S,
+            Key::D => egui::Key::D,
+            Key::Q => egui::Key::Q,
+            Key::E => egui::Key::E,
+            Key::R => egui::Key::R,
+            Key::F => egui::Key::F,
+            Key::Space => egui::Key::Space,
+            Key::Tab => egui::Key::Tab,
+            Key::ArrowUp => egui::Key::ArrowUp,
+            Key::ArrowDown => egui::Key::ArrowDown,
+            Key::ArrowLeft => egui::Key::ArrowLeft,
+            Key::ArrowRight => egui::Key::ArrowRight,
         };
     }
 
+    // fills the device-neutral input snapshot the camera controls read from this frame's egui input
+    fn input_state(ctx: &egui::Context) -> InputState
+    {
+        return ctx.input(|i|
+        {
+            let mut state = InputState::new();
+
+            for key in Key::ALL
+            {
+                if i.key_down(Self::egui_key(key))
+                {
+                    state.held.push(key);
+                }
+
+                if i.key_pressed(Self::egui_key(key))
+                {
+                    state.pressed.push(key);
+                }
+            }
+
+            let motion = i.pointer.delta();
+
+            state.primary = i.pointer.primary_down();
+            state.middle = i.pointer.middle_down();
+            state.motion = Vector::new(motion.x, motion.y, 0.0, 0.0);
+            state.scroll = i.smooth_scroll_delta.y;
+
+            state
+        });
+    }
+
     pub fn update(&mut self, ctx: &egui::Context)
     {
-        self.camera.update(self.frametime, ctx);
+        const TRANSITION_DURATION: f32 = 0.75;
+        const PATH_FILE: &str = "camera.path";
+
+        const SLOTS: [egui::Key; 9] =
+        [
+            egui::Key::Num1, egui::Key::Num2, egui::Key::Num3,
+            egui::Key::Num4, egui::Key::Num5, egui::Key::Num6,
+            egui::Key::Num7, egui::Key::Num8, egui::Key::Num9,
+        ];
+
+        let now = std::time::Instant::now();
+        self.delta = now.duration_since(self.last_update).as_secs_f32();
+        self.last_update = now;
+
+        let state = Self::input_state(ctx);
+
+        self.commands.clear();
+        self.input.poll(&state, self.frametime, self.camera.controller(), &mut self.commands);
+
+        // any manual input takes over from a running transition or path
+        if !self.commands.is_empty()
+        {
+            self.transition = None;
+
+            if let Some(timings) = self.stop_playback()
+            {
+                log::info!("path playback interrupted: {}", timings.summary());
+            }
+        }
+
+        self.camera.update(&self.commands);
+
+        // a click without a drag selects whatever is under the pointer, or clears the selection over the sky
+        let (clicked, pointer, screen) = ctx.input(|i| (i.pointer.primary_clicked(), i.pointer.interact_pos(), i.screen_rect()));
+
+        if let (true, Some(pointer)) = (clicked, pointer)
+        {
+            let u = (pointer.x - screen.min.x) / screen.width();
+            let v = (pointer.y - screen.min.y) / screen.height();
+
+            let object = self.pick_normalized(u, v).map(|query| query.object);
+            self.select(object);
+        }
+
+        if ctx.input(|i| i.key_pressed(egui::Key::F))
+        {
+            self.frame_selected();
+        }
+
+        if ctx.input(|i| i.key_pressed(egui::Key::H))
+        {
+            self.show_heatmap = !self.show_heatmap;
+        }
+
+        if ctx.input(|i| i.key_pressed(egui::Key::L))
+        {
+            self.set_spectral(!self.spectral);
+        }
+
+        if ctx.input(|i| i.key_pressed(egui::Key::B))
+        {
+            log::info!("packet benchmark: {}", self.benchmark_packets(10));
+        }
+
+        // number keys jump to a bookmark, or store the current view into that slot with ctrl held
+        for (slot, key) in SLOTS.iter().enumerate()
+        {
+            let (pressed, store) = ctx.input(|i| (i.key_pressed(*key), i.modifiers.command));
+
+            if !pressed
+            {
+                continue;
+            }
+
+            if store
+            {
+                self.store_bookmark(slot, &format!("view{}", slot + 1));
+
+                if let Some(path) = self.scene_path.clone()
+                {
+                    if let Err(error) = self.save_scene(&path)
+                    {
+                        log::warn!("failed to save {}: {}", path, error);
+                    }
+                }
+            }
+
+            else
+            {
+                self.goto_bookmark(slot, TRANSITION_DURATION);
+            }
+        }
+
+        // r toggles recording into the path file and p replays it at a fixed rate for benchmarking
+        if ctx.input(|i| i.key_pressed(egui::Key::R))
+        {
+            match self.stop_recording()
+            {
+                Some(path) => if let Err(error) = path.save(PATH_FILE)
+                {
+                    log::warn!("failed to save {}: {}", PATH_FILE, error);
+                },
+
+                None => self.start_recording(),
+            }
+        }
+
+        if ctx.input(|i| i.key_pressed(egui::Key::P)) && !self.is_recording()
+        {
+            match CameraPath::load(PATH_FILE)
+            {
+                Ok(path) => self.play(path, PlaybackRate::Fixed{ fps: 30.0 }),
+                Err(error) => log::warn!("failed to load {}: {}", PATH_FILE, error),
+            }
+        }
+
+        self.step_transition();
+        self.step_playback();
+        self.rebase();
+
+        if let Some(path) = &mut self.recording
+        {
+            path.record(self.recording_start.elapsed().as_secs_f32(), &self.camera, &self.origin);
+        }
+    }
+
+    // the primary hit, when already traced, is shared by every sample of the pixel since the camera ray only
+    // scatters after it
+    fn sample_ray(&mut self, bounces: u32, ray: Ray, primary: Option<&Intersection>) -> Vector
+    {
+        self.wavelengths = if self.spectral { Some(Wavelengths::sample(rand::rng().random())) } else { None };
+
+        let mut path = PathState::new(ray, bounces);
+
+        let pixel = match primary
+        {
+            Some(intersection) if bounces > 0 => self.shade_hit(&mut path, intersection),
+            _ => self.shade(&mut path),
+        };
+
+        // spectral samples are converted as they land but left unclipped, since a single sample can fall outside the
+        // gamut in a way that only averages out over many. the output transform clips once the pixel is displayed
+        return match self.wavelengths.take()
+        {
+            Some(wavelengths) => ColorSpace::convert(&wavelengths.to_rgb(&pixel.color), ColorSpace::LinearSrgb, self.output.working),
+            None => pixel.color,
+        };
+    }
+
+    // one sample of a pixel as linear working-space radiance, tracing both eyes for an anaglyph
+    fn sample(&mut self, x: usize, y: usize, bounces: u32, primary: Option<&Intersection>) -> Vector
+    {
+        let index = y * self.camera.width() + x;
+        let ray = self.camera.rays()[index];
+
+        if Vector::length2(&ray.direction) == 0.0
+        {
+            return Vector::new(0.0, 0.0, 0.0, 1.0);
+        }
+
+        let left = self.sample_ray(bounces, ray, primary);
+
+        let Some(partner) = self.camera.partner_rays().get(index).copied() else
+        {
+            return left;
+        };
+
+        // red from the left eye, green and blue from the right, split on the display primaries the glasses filter
+        let right = self.sample_ray(bounces, partner, None);
+
+        let left = ColorSpace::convert(&left, self.output.working, ColorSpace::LinearSrgb);
+        let right = ColorSpace::convert(&right, self.output.working, ColorSpace::LinearSrgb);
+
+        return ColorSpace::convert(&Vector::new(left.r(), right.g(), right.b(), 1.0), ColorSpace::LinearSrgb, self.output.working);
+    }
+
+    // one sample per block, stretched over the block for display, and nothing accumulated
+    fn render_downscaled(&mut self, bounces: u32, stride: usize) -> usize
+    {
+        let width = self.camera.width();
+        let height = self.camera.height();
+
+        let mut traced = 0;
+
+        for y in (0..height).step_by(stride)
+        {
+            for x in (0..width).step_by(stride)
+            {
+                let color = self.sample(x, y, bounces, None);
+                traced += 1;
+
+                for block_y in y..usize::min(y + stride, height)
+                {
+                    for block_x in x..usize::min(x + stride, width)
+                    {
+                        self.framebuffer[block_y * width + block_x] = color;
+                    }
+                }
+            }
+        }
+
+        return traced;
+    }
+
+    // traces every other pixel, alternating the pattern each frame, and copies each one into its untraced neighbour
+    fn render_checkerboard(&mut self, bounces: u32) -> usize
+    {
+        let width = self.camera.width();
+        let height = self.camera.height();
+
+        let parity = self.frames as usize & 1;
+        let mut traced = 0;
+
+        for y in 0..height
+        {
+            for x in ((y + parity) % 2..width).step_by(2)
+            {
+                let color = self.sample(x, y, bounces, None);
+                traced += 1;
+
+                self.framebuffer[y * width + x] = color;
+
+                let neighbour = if x + 1 < width { x + 1 } else { x - usize::min(x, 1) };
+                self.framebuffer[y * width + neighbour] = color;
+            }
+        }
+
+        return traced;
+    }
+
+    pub fn stereo(&self) -> Option<Stereo>
+    {
+        return self.camera.stereo();
+    }
+
+    pub fn set_stereo(&mut self, stereo: Option<Stereo>)
+    {
+        self.camera.set_stereo(stereo);
+    }
+
+    pub fn preview(&self) -> &Preview
+    {
+        return &self.preview;
+    }
+
+    pub fn preview_mut(&mut self) -> &mut Preview
+    {
+        return &mut self.preview;
     }
 
     pub fn render(&mut self, bounces: u32) 
@@ -424,45 +1844,110 @@ impl Raytracer
 
         if self.camera.moved
         {
-            self.frame_count = 1.0;
+            self.reset_accumulation();
+        }
+
+        let changed = std::mem::take(&mut self.changed);
+
+        if self.preview.enabled && self.accumulate
+        {
+            // coarse while changing, then halve the block size every frame once the view settles
+            if changed
+            {
+                self.stride = match self.preview.mode
+                {
+                    PreviewMode::Downscale => self.preview.stride(self.pixel_cost, width * height),
+                    PreviewMode::Checkerboard => 2,
+                };
+            }
+
+            else
+            {
+                self.stride = usize::max(self.stride / 2, 1);
+            }
+
+            if self.stride > 1
+            {
+                let traced = match self.preview.mode
+                {
+                    PreviewMode::Downscale => self.render_downscaled(bounces, self.stride),
+                    PreviewMode::Checkerboard => self.render_checkerboard(bounces),
+                };
 
-            self.accumulated_data.clear();
-            self.accumulated_data.resize(width * height, Vector::zero());
+                self.frametime = now.elapsed().as_secs_f32();
+                self.pixel_cost = self.frametime / usize::max(traced, 1) as f32;
+                self.converged = false;
+                self.frames += 1;
 
-            self.camera.moved = false;
+                return;
+            }
         }
 
+        // stays set only if every pixel was skipped this frame
+        let mut converged = true;
+        let mut traced = 0;
+
+        // primary rays along a row are coherent enough to trace together, the previews above sample too sparsely
+        let mut primary = Vec::with_capacity(width);
+
         for y in 0..height
         {
+            self.trace_packets(&self.camera.rays()[y * width..(y + 1) * width], &mut primary);
+
             for x in 0..width
             {
-                let mut contribution = Vector::broadcast(1.0);
-                let mut ray = self.camera.rays()[y * self.camera.width() + x];
-                let pixel = self.shade(x, y, bounces, &mut contribution, &mut ray);
-
-                let corrected = Self::gamma_correct(pixel.color, 2.2);
-                let renormalized = Self::tonemap(corrected);
-
                 let index = y * width + x;
 
-                if self.accumulate
+                if Vector::length2(&self.camera.rays()[index].direction) == 0.0
                 {
-                    self.accumulated_data[index] = Vector::add(&self.accumulated_data[index], &renormalized);
-                    self.framebuffer[index] = Vector::scale(&self.accumulated_data[index], 1.0 / self.frame_count);
+                    // outside the image circle of the projection
+                    self.framebuffer[index] = Vector::new(0.0, 0.0, 0.0, 1.0);
+                    continue;
                 }
 
-                else
+                if !self.accumulate
                 {
                     // no need to write to the accumulation buffer
-                    self.framebuffer[index] = renormalized;
+                    self.framebuffer[index] = self.sample(x, y, bounces, Some(&primary[x]));
+                    continue;
+                }
+
+                if self.sampling.is_converged(&self.estimates[index])
+                {
+                    continue;
+                }
+
+                let samples = self.sampling.samples(&self.estimates[index]);
+
+                for _ in 0..samples
+                {
+                    let radiance = self.sample(x, y, bounces, Some(&primary[x]));
+
+                    // radiance is averaged linearly, while the noise estimate judges what the sample looks like on
+                    // screen so its threshold stays perceptual
+                    self.accumulated_data[index] += radiance;
+                    self.estimates[index].add(luminance(&self.output.apply(&radiance)));
                 }
+
+                traced += samples as usize;
+
+                self.framebuffer[index] = self.accumulated_data[index] / self.estimates[index].count as f32;
+                converged = false;
             }
         }
 
+        self.converged = self.accumulate && converged;
+
         let elapsed = now.elapsed();
 
         self.frametime = elapsed.as_secs_f32();
         self.frame_count += 1.0;
+        self.frames += 1;
+
+        if traced > 0
+        {
+            self.pixel_cost = self.frametime / traced as f32;
+        }
     }
 
 }
@@ -0,0 +1,122 @@
+// (c) 2025 Connor J. Link. All Rights Reserved.
+// Luma - sampling.rs
+
+use crate::vector::*;
+
+// running mean and variance of a pixel's luminance using welford's update
+#[derive(Clone)]
+#[derive(Copy)]
+pub struct Estimate
+{
+    pub count: u32,
+    pub mean: f32,
+    m2: f32,
+}
+
+impl Estimate
+{
+    pub fn new() -> Estimate
+    {
+        return Estimate{ count: 0, mean: 0.0, m2: 0.0 };
+    }
+
+    pub fn add(&mut self, value: f32)
+    {
+        self.count += 1;
+
+        let delta = value - self.mean;
+        self.mean += delta / self.count as f32;
+        self.m2 += delta * (value - self.mean);
+    }
+
+    pub fn variance(&self) -> f32
+    {
+        if self.count < 2
+        {
+            return f32::INFINITY;
+        }
+
+        return self.m2 / (self.count - 1) as f32;
+    }
+
+    // standard error of the mean relative to the pixel brightness, floored so dark pixels can still converge
+    pub fn error(&self) -> f32
+    {
+        const FLOOR: f32 = 0.05;
+
+        let standard_error = f32::sqrt(self.variance() / self.count as f32);
+        return standard_error / f32::max(self.mean, FLOOR);
+    }
+}
+
+pub fn luminance(color: &Vector) -> f32
+{
+    return 0.2126 * color.r() + 0.7152 * color.g() + 0.0722 * color.b();
+}
+
+#[derive(Clone)]
+#[derive(Copy)]
+#[derive(Debug)]
+pub struct AdaptiveSampling
+{
+    pub enabled: bool,
+
+    // relative error below which a pixel stops receiving samples
+    pub threshold: f32,
+
+    // every pixel takes at least this many samples before its variance is trusted
+    pub min_samples: u32,
+    pub max_samples: u32,
+
+    // upper limit on the samples a single noisy pixel can take within one frame
+    pub max_per_frame: u32,
+}
+
+impl AdaptiveSampling
+{
+    pub fn new() -> AdaptiveSampling
+    {
+        return AdaptiveSampling{ enabled: true, threshold: 0.01, min_samples: 16, max_samples: 4096, max_per_frame: 4 };
+    }
+
+    pub fn is_converged(&self, estimate: &Estimate) -> bool
+    {
+        if !self.enabled
+        {
+            return false;
+        }
+
+        if estimate.count >= self.max_samples
+        {
+            return true;
+        }
+
+        return estimate.count >= self.min_samples && estimate.error() < self.threshold;
+    }
+
+    // noisier pixels take more samples per frame, in proportion to how far they are from the threshold
+    pub fn samples(&self, estimate: &Estimate) -> u32
+    {
+        if !self.enabled || estimate.count < self.min_samples
+        {
+            return 1;
+        }
+
+        let ratio = estimate.error() / self.threshold;
+        let wanted = u32::clamp(ratio as u32, 1, self.max_per_frame.max(1));
+
+        return u32::min(wanted, self.max_samples.saturating_sub(estimate.count).max(1));
+    }
+}
+
+// blue through green to red as the sample count approaches the maximum
+pub fn heatmap(count: u32, max_samples: u32) -> Vector
+{
+    let t = f32::clamp(f32::ln(1.0 + count as f32) / f32::ln(1.0 + max_samples.max(1) as f32), 0.0, 1.0);
+
+    let r = f32::clamp(2.0 * t - 1.0, 0.0, 1.0);
+    let g = 1.0 - f32::abs(2.0 * t - 1.0);
+    let b = f32::clamp(1.0 - 2.0 * t, 0.0, 1.0);
+
+    return Vector::new(r, g, b, 1.0);
+}
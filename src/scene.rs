@@ -0,0 +1,265 @@
+// (c) 2025 Connor J. Link. All Rights Reserved.
+// Luma - scene.rs
+
+use crate::vector::*;
+use crate::animation::*;
+use crate::world::*;
+
+// a saved viewpoint, with angles in radians and the field of view in degrees
+#[derive(Clone, Debug)]
+pub struct Bookmark
+{
+    pub name: String,
+    pub position: WorldPosition,
+    pub yaw: f32,
+    pub pitch: f32,
+    pub fov: f32,
+    pub focus: f32,
+}
+
+impl Bookmark
+{
+    pub fn new(name: &str, position: WorldPosition, yaw: f32, pitch: f32, fov: f32, focus: f32) -> Bookmark
+    {
+        return Bookmark{ name: name.to_owned(), position: position, yaw: yaw, pitch: pitch, fov: fov, focus: focus };
+    }
+
+    // blends two viewpoints, turning the short way around between the headings
+    pub fn lerp(bookmark1: &Bookmark, bookmark2: &Bookmark, t: f32) -> Bookmark
+    {
+        use std::f32::consts::PI;
+
+        let turn = (bookmark2.yaw - bookmark1.yaw + PI).rem_euclid(2.0 * PI) - PI;
+
+        return Bookmark
+        {
+            name: bookmark2.name.clone(),
+            position: Animatable::lerp(&bookmark1.position, &bookmark2.position, t),
+            yaw: bookmark1.yaw + turn * t,
+            pitch: Animatable::lerp(&bookmark1.pitch, &bookmark2.pitch, t),
+            fov: Animatable::lerp(&bookmark1.fov, &bookmark2.fov, t),
+            focus: Animatable::lerp(&bookmark1.focus, &bookmark2.focus, t),
+        };
+    }
+}
+
+// line-based scene description with one directive per line and '#' comments:
+//   sun <x> <y> <z>
+//   bookmark <name> <x> <y> <z> <yaw> <pitch> <fov> <focus>
+//   empty
+// bookmarks fill slots in order, with 'empty' holding an unused slot. names are double quoted with backslash escapes,
+// so they may contain whitespace or '#', but not be empty
+pub struct SceneFile
+{
+    pub sun: Option<Vector>,
+    pub bookmarks: Vec<Option<Bookmark>>,
+}
+
+impl SceneFile
+{
+    pub fn new() -> SceneFile
+    {
+        return SceneFile{ sun: None, bookmarks: Vec::new() };
+    }
+
+    // splits a line into fields, keeping quoted strings whole and stopping at a '#' outside of them
+    fn fields(content: &str, line: usize) -> std::io::Result<Vec<String>>
+    {
+        let mut fields = Vec::new();
+        let mut chars = content.chars().peekable();
+
+        while let Some(&c) = chars.peek()
+        {
+            if c.is_whitespace()
+            {
+                chars.next();
+            }
+
+            else if c == '#'
+            {
+                break;
+            }
+
+            else if c == '"'
+            {
+                chars.next();
+
+                let mut field = String::new();
+                let mut closed = false;
+
+                while let Some(c) = chars.next()
+                {
+                    match c
+                    {
+                        '"' =>
+                        {
+                            closed = true;
+                            break;
+                        }
+
+                        '\\' => match chars.next()
+                        {
+                            Some(escaped) => field.push(escaped),
+                            None => break,
+                        },
+
+                        _ => field.push(c),
+                    }
+                }
+
+                if !closed
+                {
+                    return Err(std::io::Error::new(std::io::ErrorKind::InvalidData, format!("line {}: unterminated string", line)));
+                }
+
+                fields.push(field);
+            }
+
+            else
+            {
+                let mut field = String::new();
+
+                while let Some(&c) = chars.peek()
+                {
+                    if c.is_whitespace() || c == '#'
+                    {
+                        break;
+                    }
+
+                    field.push(c);
+                    chars.next();
+                }
+
+                fields.push(field);
+            }
+        }
+
+        return Ok(fields);
+    }
+
+    fn quote(name: &str) -> String
+    {
+        let mut quoted = String::from("\"");
+
+        for c in name.chars()
+        {
+            if c == '"' || c == '\\'
+            {
+                quoted.push('\\');
+            }
+
+            quoted.push(c);
+        }
+
+        quoted.push('"');
+        return quoted;
+    }
+
+    fn numbers<T: std::str::FromStr>(fields: &[String], count: usize, line: usize) -> std::io::Result<Vec<T>>
+    {
+        if fields.len() != count
+        {
+            return Err(std::io::Error::new(std::io::ErrorKind::InvalidData, format!("line {}: expected {} values, found {}", line, count, fields.len())));
+        }
+
+        let mut values = Vec::with_capacity(count);
+
+        for field in fields
+        {
+            match field.parse::<T>()
+            {
+                Ok(value) => values.push(value),
+                Err(_) => return Err(std::io::Error::new(std::io::ErrorKind::InvalidData, format!("line {}: invalid number '{}'", line, field))),
+            }
+        }
+
+        return Ok(values);
+    }
+
+    pub fn parse(text: &str) -> std::io::Result<SceneFile>
+    {
+        let mut scene = SceneFile::new();
+
+        for (index, raw) in text.lines().enumerate()
+        {
+            let line = index + 1;
+            let fields = Self::fields(raw, line)?;
+
+            let Some(directive) = fields.first() else
+            {
+                continue;
+            };
+
+            match directive.as_str()
+            {
+                "sun" =>
+                {
+                    let v = Self::numbers::<f32>(&fields[1..], 3, line)?;
+                    scene.sun = Some(Vector::new(v[0], v[1], v[2], 1.0));
+                }
+
+                "bookmark" =>
+                {
+                    if fields.len() < 2 || fields[1].is_empty()
+                    {
+                        return Err(std::io::Error::new(std::io::ErrorKind::InvalidData, format!("line {}: bookmark without a name", line)));
+                    }
+
+                    // read in double precision so positions far from the origin survive the round trip
+                    let v = Self::numbers::<f64>(&fields[2..], 7, line)?;
+                    let position = WorldPosition::new(v[0], v[1], v[2]);
+
+                    scene.bookmarks.push(Some(Bookmark::new(&fields[1], position, v[3] as f32, v[4] as f32, v[5] as f32, v[6] as f32)));
+                }
+
+                "empty" => scene.bookmarks.push(None),
+
+                _ => return Err(std::io::Error::new(std::io::ErrorKind::InvalidData, format!("line {}: unknown directive '{}'", line, directive))),
+            }
+        }
+
+        return Ok(scene);
+    }
+
+    pub fn serialize(&self) -> std::io::Result<String>
+    {
+        let mut text = String::from("# luma scene\n");
+
+        if let Some(sun) = self.sun
+        {
+            text.push_str(&format!("sun {} {} {}\n", sun.x(), sun.y(), sun.z()));
+        }
+
+        for slot in &self.bookmarks
+        {
+            let Some(bookmark) = slot else
+            {
+                text.push_str("empty\n");
+                continue;
+            };
+
+            if bookmark.name.is_empty()
+            {
+                return Err(std::io::Error::new(std::io::ErrorKind::InvalidInput, "bookmark without a name"));
+            }
+
+            let name = Self::quote(&bookmark.name);
+            let position = bookmark.position;
+
+            text.push_str(&format!("bookmark {} {} {} {} {} {} {} {}\n", name, position.x, position.y, position.z, bookmark.yaw, bookmark.pitch, bookmark.fov, bookmark.focus));
+        }
+
+        return Ok(text);
+    }
+
+    pub fn load(path: &str) -> std::io::Result<SceneFile>
+    {
+        let text = std::fs::read_to_string(path)?;
+        return SceneFile::parse(&text);
+    }
+
+    pub fn save(&self, path: &str) -> std::io::Result<()>
+    {
+        return std::fs::write(path, self.serialize()?);
+    }
+}
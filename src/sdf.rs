@@ -0,0 +1,350 @@
+// (c) 2025 Connor J. Link. All Rights Reserved.
+// Luma - sdf.rs
+
+use crate::vector::*;
+use crate::ray::*;
+use crate::shape::*;
+use crate::bounds::*;
+
+// signed distance expressions after inigo quilez, negative inside the solid
+pub enum Sdf
+{
+    Sphere{ radius: f32 },
+    Cuboid{ extents: Vector },
+    RoundBox{ extents: Vector, radius: f32 },
+    Torus{ major: f32, minor: f32 },
+    Capsule{ start: Vector, end: Vector, radius: f32 },
+    Cylinder{ radius: f32, height: f32 },
+
+    Translate{ offset: Vector, child: Box<Sdf> },
+
+    Union(Box<Sdf>, Box<Sdf>),
+    Intersection(Box<Sdf>, Box<Sdf>),
+    Difference(Box<Sdf>, Box<Sdf>),
+
+    // polynomial smooth minimum blending over the distance k
+    SmoothUnion{ k: f32, left: Box<Sdf>, right: Box<Sdf> },
+    SmoothIntersection{ k: f32, left: Box<Sdf>, right: Box<Sdf> },
+    SmoothDifference{ k: f32, left: Box<Sdf>, right: Box<Sdf> },
+
+    // copies of the child every spacing units, limited to count copies either side of the origin per axis
+    Repeat{ spacing: Vector, count: Vector, child: Box<Sdf> },
+
+    // rotates the xz plane by rate radians per unit of height
+    Twist{ rate: f32, child: Box<Sdf> },
+}
+
+fn abs(vec1: &Vector) -> Vector
+{
+    return Vector::new(f32::abs(vec1.x()), f32::abs(vec1.y()), f32::abs(vec1.z()), 0.0);
+}
+
+fn positive(vec1: &Vector) -> Vector
+{
+    return Vector::new(f32::max(vec1.x(), 0.0), f32::max(vec1.y(), 0.0), f32::max(vec1.z(), 0.0), 0.0);
+}
+
+fn mix(a: f32, b: f32, t: f32) -> f32
+{
+    return a + (b - a) * t;
+}
+
+impl Sdf
+{
+    pub fn distance(&self, p: &Vector) -> f32
+    {
+        return match self
+        {
+            Sdf::Sphere{ radius } => Vector::length(p) - radius,
+
+            Sdf::Cuboid{ extents } =>
+            {
+                let q = Vector::sub(&abs(p), extents);
+                Vector::length(&positive(&q)) + f32::min(f32::max(q.x(), f32::max(q.y(), q.z())), 0.0)
+            }
+
+            Sdf::RoundBox{ extents, radius } =>
+            {
+                let inner = Vector::sub(extents, &Vector::new(*radius, *radius, *radius, 0.0));
+                let q = Vector::sub(&abs(p), &inner);
+                Vector::length(&positive(&q)) + f32::min(f32::max(q.x(), f32::max(q.y(), q.z())), 0.0) - radius
+            }
+
+            Sdf::Torus{ major, minor } =>
+            {
+                let ring = f32::sqrt(p.x() * p.x() + p.z() * p.z()) - major;
+                f32::sqrt(ring * ring + p.y() * p.y()) - minor
+            }
+
+            Sdf::Capsule{ start, end, radius } =>
+            {
+                let pa = Vector::sub(p, start);
+                let ba = Vector::sub(end, start);
+                let h = f32::clamp(Vector::dot(&pa, &ba) / Vector::dot(&ba, &ba), 0.0, 1.0);
+                Vector::length(&Vector::sub(&pa, &Vector::scale(&ba, h))) - radius
+            }
+
+            Sdf::Cylinder{ radius, height } =>
+            {
+                let dx = f32::sqrt(p.x() * p.x() + p.z() * p.z()) - radius;
+                let dy = f32::abs(p.y()) - 0.5 * height;
+                let outside = f32::sqrt(f32::max(dx, 0.0) * f32::max(dx, 0.0) + f32::max(dy, 0.0) * f32::max(dy, 0.0));
+                f32::min(f32::max(dx, dy), 0.0) + outside
+            }
+
+            Sdf::Translate{ offset, child } => child.distance(&Vector::sub(p, offset)),
+
+            Sdf::Union(left, right) => f32::min(left.distance(p), right.distance(p)),
+            Sdf::Intersection(left, right) => f32::max(left.distance(p), right.distance(p)),
+            Sdf::Difference(left, right) => f32::max(left.distance(p), -right.distance(p)),
+
+            Sdf::SmoothUnion{ k, left, right } =>
+            {
+                let a = left.distance(p);
+                let b = right.distance(p);
+                let h = f32::clamp(0.5 + 0.5 * (b - a) / k, 0.0, 1.0);
+                mix(b, a, h) - k * h * (1.0 - h)
+            }
+
+            Sdf::SmoothIntersection{ k, left, right } =>
+            {
+                let a = left.distance(p);
+                let b = right.distance(p);
+                let h = f32::clamp(0.5 - 0.5 * (b - a) / k, 0.0, 1.0);
+                mix(b, a, h) + k * h * (1.0 - h)
+            }
+
+            Sdf::SmoothDifference{ k, left, right } =>
+            {
+                let a = left.distance(p);
+                let b = right.distance(p);
+                let h = f32::clamp(0.5 - 0.5 * (a + b) / k, 0.0, 1.0);
+                mix(a, -b, h) + k * h * (1.0 - h)
+            }
+
+            Sdf::Repeat{ spacing, count, child } =>
+            {
+                let cell = |x: f32, s: f32, n: f32| x - s * f32::clamp(f32::round(x / s), -n, n);
+                let q = Vector::new(cell(p.x(), spacing.x(), count.x()), cell(p.y(), spacing.y(), count.y()), cell(p.z(), spacing.z(), count.z()), 1.0);
+                child.distance(&q)
+            }
+
+            Sdf::Twist{ rate, child } =>
+            {
+                let c = f32::cos(rate * p.y());
+                let s = f32::sin(rate * p.y());
+                let q = Vector::new(c * p.x() - s * p.z(), p.y(), s * p.x() + c * p.z(), 1.0);
+                child.distance(&q)
+            }
+        };
+    }
+
+    pub fn bounds(&self) -> Aabb
+    {
+        return match self
+        {
+            Sdf::Sphere{ radius } => Aabb::new(Vector::new(-radius, -radius, -radius, 1.0), Vector::new(*radius, *radius, *radius, 1.0)),
+            Sdf::Cuboid{ extents } | Sdf::RoundBox{ extents, .. } => Aabb::new(Vector::scale(extents, -1.0), *extents),
+
+            Sdf::Torus{ major, minor } =>
+            {
+                let outer = major + minor;
+                Aabb::new(Vector::new(-outer, -minor, -outer, 1.0), Vector::new(outer, *minor, outer, 1.0))
+            }
+
+            Sdf::Capsule{ start, end, radius } =>
+            {
+                let segment = Aabb::include(&Aabb::include(&Aabb::empty(), start), end);
+                Aabb::expand(&segment, *radius)
+            }
+
+            Sdf::Cylinder{ radius, height } => Aabb::new(Vector::new(-radius, -0.5 * height, -radius, 1.0), Vector::new(*radius, 0.5 * height, *radius, 1.0)),
+
+            Sdf::Translate{ offset, child } =>
+            {
+                let inner = child.bounds();
+                Aabb::new(Vector::add(&inner.min, offset), Vector::add(&inner.max, offset))
+            }
+
+            Sdf::Union(left, right) => Aabb::union(&left.bounds(), &right.bounds()),
+            Sdf::Intersection(left, right) => Aabb::intersection(&left.bounds(), &right.bounds()),
+            Sdf::Difference(left, _) => left.bounds(),
+
+            // the smooth minimum can bulge out by at most a quarter of the blend distance
+            Sdf::SmoothUnion{ k, left, right } => Aabb::expand(&Aabb::union(&left.bounds(), &right.bounds()), 0.25 * k),
+            Sdf::SmoothIntersection{ k, left, right } => Aabb::expand(&Aabb::intersection(&left.bounds(), &right.bounds()), 0.25 * k),
+            Sdf::SmoothDifference{ k, left, .. } => Aabb::expand(&left.bounds(), 0.25 * k),
+
+            Sdf::Repeat{ spacing, count, child } =>
+            {
+                let inner = child.bounds();
+                let reach = Vector::mul(spacing, count);
+                Aabb::new(Vector::sub(&inner.min, &reach), Vector::add(&inner.max, &reach))
+            }
+
+            Sdf::Twist{ child, .. } =>
+            {
+                // any rotation about y stays within the cylinder through the farthest corner
+                let inner = child.bounds();
+                let x = f32::max(f32::abs(inner.min.x()), f32::abs(inner.max.x()));
+                let z = f32::max(f32::abs(inner.min.z()), f32::abs(inner.max.z()));
+                let radius = f32::sqrt(x * x + z * z);
+                Aabb::new(Vector::new(-radius, inner.min.y(), -radius, 1.0), Vector::new(radius, inner.max.y(), radius, 1.0))
+            }
+        };
+    }
+}
+
+// an sdf expression rendered by sphere tracing inside its bounding box
+pub struct DistanceField
+{
+    root: Sdf,
+    bounds: Aabb,
+
+    pub max_steps: u32,
+    pub epsilon: f32,
+
+    // fraction of the safe distance taken per step, below one for non-lipschitz operators such as twists
+    pub step_scale: f32,
+}
+
+impl DistanceField
+{
+    pub fn new(root: Sdf) -> DistanceField
+    {
+        let bounds = root.bounds();
+        return DistanceField{ root: root, bounds: bounds, max_steps: 256, epsilon: 0.0005, step_scale: 1.0 };
+    }
+
+    pub fn root(&self) -> &Sdf
+    {
+        return &self.root;
+    }
+
+    pub fn normal(&self, p: &Vector) -> Vector
+    {
+        // tetrahedral gradient estimate needs four evaluations instead of six
+        let h = 2.0 * self.epsilon;
+
+        let k1 = Vector::new(1.0, -1.0, -1.0, 0.0);
+        let k2 = Vector::new(-1.0, -1.0, 1.0, 0.0);
+        let k3 = Vector::new(-1.0, 1.0, -1.0, 0.0);
+        let k4 = Vector::new(1.0, 1.0, 1.0, 0.0);
+
+        let mut gradient = Vector::zero();
+
+        for k in [k1, k2, k3, k4]
+        {
+            let sample = self.root.distance(&Vector::add(p, &Vector::scale(&k, h)));
+            gradient = Vector::add(&gradient, &Vector::scale(&k, sample));
+        }
+
+        return Vector::normalize(&gradient);
+    }
+
+    // sphere traces between two distances, recording up to limit surface crossings
+    fn march(&self, ray: &Ray, start: f32, end: f32, limit: usize, crossings: &mut Vec<Crossing>)
+    {
+        let speed = Vector::length(&ray.direction);
+
+        if speed == 0.0
+        {
+            return;
+        }
+
+        let mut t = start;
+        let mut inside = self.root.distance(&Vector::add(&ray.origin, &Vector::scale(&ray.direction, t))) < 0.0;
+        let mut leaving = false;
+
+        for _ in 0..self.max_steps
+        {
+            if t > end || crossings.len() >= limit
+            {
+                return;
+            }
+
+            let p = Vector::add(&ray.origin, &Vector::scale(&ray.direction, t));
+            let d = self.root.distance(&p);
+
+            // distance to the surface as seen from the current side
+            let gap = if inside { -d } else { d };
+
+            if leaving
+            {
+                // step past the surface just recorded before looking for the next one
+                if f32::abs(d) >= self.epsilon
+                {
+                    leaving = false;
+                }
+
+                t += f32::max(f32::abs(d) * self.step_scale, self.epsilon) / speed;
+                continue;
+            }
+
+            if gap < self.epsilon
+            {
+                let normal = self.normal(&p);
+                crossings.push(Crossing::new(t, normal, Vector::zero()));
+
+                inside = !inside;
+                leaving = true;
+
+                t += self.epsilon / speed;
+                continue;
+            }
+
+            t += gap * self.step_scale / speed;
+        }
+    }
+}
+
+impl Shape for DistanceField
+{
+    fn intersect(&self, ray: &Ray) -> Option<Hit>
+    {
+        let (near, far) = Aabb::intersect(&self.bounds, ray)?;
+
+        if far <= 0.0
+        {
+            return None;
+        }
+
+        let mut crossings = Vec::with_capacity(2);
+        self.march(ray, f32::max(near, 0.0), far, 2, &mut crossings);
+
+        let mut hit = closest(ray, &crossings)?;
+
+        // the march stops anywhere within epsilon of the surface, so rays leaving the hit must start clear of that
+        // band or they find the same surface again on their first step
+        hit.error = Vector::add(&hit.error, &Vector::new(2.0 * self.epsilon, 2.0 * self.epsilon, 2.0 * self.epsilon, 0.0));
+
+        return Some(hit);
+    }
+
+    fn bounds(&self) -> Aabb
+    {
+        return self.bounds;
+    }
+
+    fn spans(&self, ray: &Ray, spans: &mut Vec<Span>)
+    {
+        let Some((near, far)) = Aabb::intersect(&self.bounds, ray) else
+        {
+            return;
+        };
+
+        let mut crossings = Vec::new();
+
+        // a field already negative at the box wall starts its first span there
+        let entry = Vector::add(&ray.origin, &Vector::scale(&ray.direction, near));
+
+        if self.root.distance(&entry) < 0.0
+        {
+            crossings.push(Crossing::new(near, self.normal(&entry), Vector::zero()));
+        }
+
+        self.march(ray, near, far, usize::MAX, &mut crossings);
+
+        pair(&mut crossings, spans);
+    }
+}
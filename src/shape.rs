@@ -0,0 +1,135 @@
+// (c) 2025 Connor J. Link. All Rights Reserved.
+// Luma - shape.rs
+
+use crate::vector::*;
+use crate::ray::*;
+use crate::bounds::*;
+
+#[derive(Clone)]
+#[derive(Copy)]
+pub struct Hit
+{
+    pub position: Vector,
+    pub normal: Vector,
+    pub uv: Vector,
+    pub distance: f32,
+    pub exit: f32,
+
+    // per-axis bound on the rounding error in position, used to spawn rays clear of the surface
+    pub error: Vector,
+}
+
+// a single place where a ray line passes through the surface of a shape
+#[derive(Clone)]
+#[derive(Copy)]
+pub struct Crossing
+{
+    pub distance: f32,
+    pub normal: Vector,
+    pub uv: Vector,
+}
+
+impl Crossing
+{
+    pub fn new(distance: f32, normal: Vector, uv: Vector) -> Crossing
+    {
+        return Crossing{ distance: distance, normal: normal, uv: uv };
+    }
+}
+
+// an interval of the ray line spent inside a solid, with both normals pointing out of the solid
+#[derive(Clone)]
+#[derive(Copy)]
+pub struct Span
+{
+    pub enter: Crossing,
+    pub exit: Crossing,
+}
+
+impl Span
+{
+    pub fn new(enter: Crossing, exit: Crossing) -> Span
+    {
+        return Span{ enter: enter, exit: exit };
+    }
+}
+
+pub trait Shape
+{
+    // closest hit in front of the ray origin, with distances measured in units of the ray direction
+    fn intersect(&self, ray: &Ray) -> Option<Hit>;
+
+    fn bounds(&self) -> Aabb;
+
+    // every interval of the whole ray line inside the shape, sorted and disjoint
+    fn spans(&self, ray: &Ray, spans: &mut Vec<Span>);
+}
+
+// picks the nearest crossing in front of the ray origin, reporting the farthest one as the exit
+pub fn closest(ray: &Ray, crossings: &[Crossing]) -> Option<Hit>
+{
+    let mut nearest: Option<&Crossing> = None;
+    let mut exit = -f32::MAX;
+
+    for crossing in crossings
+    {
+        exit = f32::max(exit, crossing.distance);
+
+        if crossing.distance <= 0.0
+        {
+            continue;
+        }
+
+        if nearest.map_or(true, |n| crossing.distance < n.distance)
+        {
+            nearest = Some(crossing);
+        }
+    }
+
+    let nearest = nearest?;
+    let position = ray.at(nearest.distance);
+    let error = ray.error(nearest.distance);
+
+    return Some(Hit{ position: position, normal: nearest.normal, uv: nearest.uv, distance: nearest.distance, exit: exit, error: error });
+}
+
+// sorts the crossings of a closed surface and pairs them into entry and exit spans
+pub fn pair(crossings: &mut Vec<Crossing>, spans: &mut Vec<Span>)
+{
+    crossings.sort_by(|a, b| a.distance.total_cmp(&b.distance));
+
+    // a tangent graze produces an odd count, so the unpaired crossing is dropped
+    for chunk in crossings.chunks_exact(2)
+    {
+        spans.push(Span::new(chunk[0], chunk[1]));
+    }
+}
+
+// picks the first span boundary in front of the ray origin
+pub fn first(ray: &Ray, spans: &[Span]) -> Option<Hit>
+{
+    for span in spans
+    {
+        let crossing = if span.enter.distance > 0.0
+        {
+            span.enter
+        }
+
+        else if span.exit.distance > 0.0
+        {
+            span.exit
+        }
+
+        else
+        {
+            continue;
+        };
+
+        let position = ray.at(crossing.distance);
+        let error = ray.error(crossing.distance);
+
+        return Some(Hit{ position: position, normal: crossing.normal, uv: crossing.uv, distance: crossing.distance, exit: span.exit.distance, error: error });
+    }
+
+    return None;
+}
@@ -0,0 +1,195 @@
+// (c) 2025 Connor J. Link. All Rights Reserved.
+// Luma - simd.rs
+
+// four-wide float kernels for the vector and matrix types: sse on x86_64 and neon on aarch64, both part of
+// the baseline instruction set so no runtime detection is needed, with plain loops everywhere else
+
+#[cfg(target_arch = "x86_64")]
+use std::arch::x86_64::*;
+
+#[cfg(target_arch = "aarch64")]
+use std::arch::aarch64::*;
+
+#[cfg(target_arch = "x86_64")]
+pub fn add(a: [f32; 4], b: [f32; 4]) -> [f32; 4]
+{
+    let mut result = [0.0; 4];
+
+    unsafe
+    {
+        _mm_storeu_ps(result.as_mut_ptr(), _mm_add_ps(_mm_loadu_ps(a.as_ptr()), _mm_loadu_ps(b.as_ptr())));
+    }
+
+    return result;
+}
+
+#[cfg(target_arch = "x86_64")]
+pub fn sub(a: [f32; 4], b: [f32; 4]) -> [f32; 4]
+{
+    let mut result = [0.0; 4];
+
+    unsafe
+    {
+        _mm_storeu_ps(result.as_mut_ptr(), _mm_sub_ps(_mm_loadu_ps(a.as_ptr()), _mm_loadu_ps(b.as_ptr())));
+    }
+
+    return result;
+}
+
+#[cfg(target_arch = "x86_64")]
+pub fn mul(a: [f32; 4], b: [f32; 4]) -> [f32; 4]
+{
+    let mut result = [0.0; 4];
+
+    unsafe
+    {
+        _mm_storeu_ps(result.as_mut_ptr(), _mm_mul_ps(_mm_loadu_ps(a.as_ptr()), _mm_loadu_ps(b.as_ptr())));
+    }
+
+    return result;
+}
+
+#[cfg(target_arch = "x86_64")]
+pub fn scale(a: [f32; 4], scalar: f32) -> [f32; 4]
+{
+    let mut result = [0.0; 4];
+
+    unsafe
+    {
+        _mm_storeu_ps(result.as_mut_ptr(), _mm_mul_ps(_mm_loadu_ps(a.as_ptr()), _mm_set1_ps(scalar)));
+    }
+
+    return result;
+}
+
+// row vector times matrix: a weighted sum of the matrix rows
+#[cfg(target_arch = "x86_64")]
+pub fn transform(v: [f32; 4], m: &[[f32; 4]; 4]) -> [f32; 4]
+{
+    let mut result = [0.0; 4];
+
+    unsafe
+    {
+        let mut sum = _mm_mul_ps(_mm_set1_ps(v[0]), _mm_loadu_ps(m[0].as_ptr()));
+        sum = _mm_add_ps(sum, _mm_mul_ps(_mm_set1_ps(v[1]), _mm_loadu_ps(m[1].as_ptr())));
+        sum = _mm_add_ps(sum, _mm_mul_ps(_mm_set1_ps(v[2]), _mm_loadu_ps(m[2].as_ptr())));
+        sum = _mm_add_ps(sum, _mm_mul_ps(_mm_set1_ps(v[3]), _mm_loadu_ps(m[3].as_ptr())));
+
+        _mm_storeu_ps(result.as_mut_ptr(), sum);
+    }
+
+    return result;
+}
+
+#[cfg(target_arch = "aarch64")]
+pub fn add(a: [f32; 4], b: [f32; 4]) -> [f32; 4]
+{
+    let mut result = [0.0; 4];
+
+    unsafe
+    {
+        vst1q_f32(result.as_mut_ptr(), vaddq_f32(vld1q_f32(a.as_ptr()), vld1q_f32(b.as_ptr())));
+    }
+
+    return result;
+}
+
+#[cfg(target_arch = "aarch64")]
+pub fn sub(a: [f32; 4], b: [f32; 4]) -> [f32; 4]
+{
+    let mut result = [0.0; 4];
+
+    unsafe
+    {
+        vst1q_f32(result.as_mut_ptr(), vsubq_f32(vld1q_f32(a.as_ptr()), vld1q_f32(b.as_ptr())));
+    }
+
+    return result;
+}
+
+#[cfg(target_arch = "aarch64")]
+pub fn mul(a: [f32; 4], b: [f32; 4]) -> [f32; 4]
+{
+    let mut result = [0.0; 4];
+
+    unsafe
+    {
+        vst1q_f32(result.as_mut_ptr(), vmulq_f32(vld1q_f32(a.as_ptr()), vld1q_f32(b.as_ptr())));
+    }
+
+    return result;
+}
+
+#[cfg(target_arch = "aarch64")]
+pub fn scale(a: [f32; 4], scalar: f32) -> [f32; 4]
+{
+    let mut result = [0.0; 4];
+
+    unsafe
+    {
+        vst1q_f32(result.as_mut_ptr(), vmulq_n_f32(vld1q_f32(a.as_ptr()), scalar));
+    }
+
+    return result;
+}
+
+#[cfg(target_arch = "aarch64")]
+pub fn transform(v: [f32; 4], m: &[[f32; 4]; 4]) -> [f32; 4]
+{
+    let mut result = [0.0; 4];
+
+    unsafe
+    {
+        let mut sum = vmulq_n_f32(vld1q_f32(m[0].as_ptr()), v[0]);
+        sum = vfmaq_n_f32(sum, vld1q_f32(m[1].as_ptr()), v[1]);
+        sum = vfmaq_n_f32(sum, vld1q_f32(m[2].as_ptr()), v[2]);
+        sum = vfmaq_n_f32(sum, vld1q_f32(m[3].as_ptr()), v[3]);
+
+        vst1q_f32(result.as_mut_ptr(), sum);
+    }
+
+    return result;
+}
+
+#[cfg(not(any(target_arch = "x86_64", target_arch = "aarch64")))]
+pub fn add(a: [f32; 4], b: [f32; 4]) -> [f32; 4]
+{
+    return [a[0] + b[0], a[1] + b[1], a[2] + b[2], a[3] + b[3]];
+}
+
+#[cfg(not(any(target_arch = "x86_64", target_arch = "aarch64")))]
+pub fn sub(a: [f32; 4], b: [f32; 4]) -> [f32; 4]
+{
+    return [a[0] - b[0], a[1] - b[1], a[2] - b[2], a[3] - b[3]];
+}
+
+#[cfg(not(any(target_arch = "x86_64", target_arch = "aarch64")))]
+pub fn mul(a: [f32; 4], b: [f32; 4]) -> [f32; 4]
+{
+    return [a[0] * b[0], a[1] * b[1], a[2] * b[2], a[3] * b[3]];
+}
+
+#[cfg(not(any(target_arch = "x86_64", target_arch = "aarch64")))]
+pub fn scale(a: [f32; 4], scalar: f32) -> [f32; 4]
+{
+    return [a[0] * scalar, a[1] * scalar, a[2] * scalar, a[3] * scalar];
+}
+
+#[cfg(not(any(target_arch = "x86_64", target_arch = "aarch64")))]
+pub fn transform(v: [f32; 4], m: &[[f32; 4]; 4]) -> [f32; 4]
+{
+    let mut result = [0.0; 4];
+
+    for j in 0..4
+    {
+        result[j] = v[0] * m[0][j] + v[1] * m[1][j] + v[2] * m[2][j] + v[3] * m[3][j];
+    }
+
+    return result;
+}
+
+// each row of the product is the row of the left matrix transformed by the right one
+pub fn matrix_mul(a: &[[f32; 4]; 4], b: &[[f32; 4]; 4]) -> [[f32; 4]; 4]
+{
+    return [transform(a[0], b), transform(a[1], b), transform(a[2], b), transform(a[3], b)];
+}
@@ -0,0 +1,218 @@
+// (c) 2025 Connor J. Link. All Rights Reserved.
+// Luma - spectrum.rs
+
+use std::sync::OnceLock;
+
+use crate::vector::*;
+use crate::color::*;
+
+// sampled range of visible wavelengths in nanometres
+pub const LAMBDA_MIN: f32 = 380.0;
+pub const LAMBDA_MAX: f32 = 720.0;
+
+// one wavelength per lane of a Vector
+pub const SAMPLES: usize = 4;
+
+// smits' basis spectra for rgb to reflectance uplift (smits 1999), in ten bins evenly covering the sampled range
+const WHITE: [f32; 10] = [1.0000, 1.0000, 0.9999, 0.9993, 0.9992, 0.9998, 1.0000, 1.0000, 1.0000, 1.0000];
+const CYAN: [f32; 10] = [0.9710, 0.9426, 1.0007, 1.0007, 1.0007, 1.0007, 0.1564, 0.0000, 0.0000, 0.0000];
+const MAGENTA: [f32; 10] = [1.0000, 1.0000, 0.9685, 0.2229, 0.0000, 0.0458, 0.8369, 1.0000, 1.0000, 0.9959];
+const YELLOW: [f32; 10] = [0.0001, 0.0000, 0.1088, 0.6651, 1.0000, 1.0000, 0.9996, 0.9586, 0.9685, 0.9840];
+const RED: [f32; 10] = [0.1012, 0.0515, 0.0000, 0.0000, 0.0000, 0.0000, 0.8325, 1.0149, 1.0149, 1.0149];
+const GREEN: [f32; 10] = [0.0000, 0.0000, 0.0273, 0.7937, 1.0000, 0.9418, 0.1719, 0.0000, 0.0000, 0.0025];
+const BLUE: [f32; 10] = [1.0000, 1.0000, 0.8916, 0.3323, 0.0000, 0.0000, 0.0003, 0.0369, 0.0483, 0.0496];
+
+// the wavelengths carried by one path, with the probability density each was sampled with
+#[derive(Clone)]
+#[derive(Copy)]
+#[derive(Debug)]
+pub struct Wavelengths
+{
+    lambda: [f32; SAMPLES],
+    pdf: [f32; SAMPLES],
+}
+
+impl Wavelengths
+{
+    // hero wavelength sampling (wilkie et al. 2014): one uniformly chosen wavelength plus the others rotated evenly
+    // around the range, so every path sees the whole spectrum and the colour noise mostly cancels
+    pub fn sample(u: f32) -> Wavelengths
+    {
+        let range = LAMBDA_MAX - LAMBDA_MIN;
+        let hero = LAMBDA_MIN + f32::clamp(u, 0.0, 1.0) * range;
+
+        let mut lambda = [0.0; SAMPLES];
+
+        for i in 0..SAMPLES
+        {
+            let mut rotated = hero + i as f32 * range / SAMPLES as f32;
+
+            if rotated > LAMBDA_MAX
+            {
+                rotated -= range;
+            }
+
+            lambda[i] = rotated;
+        }
+
+        return Wavelengths{ lambda: lambda, pdf: [1.0 / range; SAMPLES] };
+    }
+
+    pub fn lambda(&self, i: usize) -> f32
+    {
+        return self.lambda[i];
+    }
+
+    pub fn hero(&self) -> f32
+    {
+        return self.lambda[0];
+    }
+
+    // keeps only the hero wavelength, for paths that can't be shared between wavelengths such as refraction
+    // through dispersive glass. its density is divided down so the estimate keeps the same expected value
+    pub fn terminate_secondary(&mut self)
+    {
+        if self.is_terminated()
+        {
+            return;
+        }
+
+        for i in 1..SAMPLES
+        {
+            self.pdf[i] = 0.0;
+        }
+
+        self.pdf[0] /= SAMPLES as f32;
+    }
+
+    pub fn is_terminated(&self) -> bool
+    {
+        return self.pdf[1..].iter().all(|pdf| *pdf == 0.0);
+    }
+
+    // reflectance of an rgb colour at each of the wavelengths
+    pub fn uplift(&self, rgb: &Vector) -> Vector
+    {
+        return Vector::new(uplift(rgb, self.lambda[0]), uplift(rgb, self.lambda[1]), uplift(rgb, self.lambda[2]), uplift(rgb, self.lambda[3]));
+    }
+
+    // monte carlo estimate of cie xyz from a value per wavelength, scaled so a constant 1 spectrum has y = 1
+    pub fn to_xyz(&self, values: &Vector) -> Vector
+    {
+        let values = values.raw();
+        let mut xyz = [0.0; 3];
+
+        for i in 0..SAMPLES
+        {
+            if self.pdf[i] == 0.0
+            {
+                continue;
+            }
+
+            let (x, y, z) = cie_xyz(self.lambda[i]);
+            let weight = values[i] / self.pdf[i];
+
+            xyz[0] += x * weight;
+            xyz[1] += y * weight;
+            xyz[2] += z * weight;
+        }
+
+        let scale = 1.0 / (SAMPLES as f32 * integrals().y);
+        return Vector::new(xyz[0] * scale, xyz[1] * scale, xyz[2] * scale, 0.0);
+    }
+
+    // linear srgb, balanced so a constant spectrum comes out neutral. a single sample can land well outside the
+    // gamut with negative channels, which only average out over many samples, so nothing is clipped here
+    pub fn to_rgb(&self, values: &Vector) -> Vector
+    {
+        let rgb = ColorSpace::LinearSrgb.from_xyz(&self.to_xyz(values));
+        let white = integrals().white;
+
+        return Vector::new(rgb.x() / white[0], rgb.y() / white[1], rgb.z() / white[2], 1.0);
+    }
+}
+
+// smits' uplift: the grey part from the white spectrum, then the remaining two channels from the secondary and
+// primary spectra that cover them, evaluated by linear interpolation between bin centres
+pub fn uplift(rgb: &Vector, lambda: f32) -> f32
+{
+    let (r, g, b) = (rgb.x(), rgb.y(), rgb.z());
+
+    let basis = |spectrum: &[f32; 10]|
+    {
+        let position = (lambda - LAMBDA_MIN) / (LAMBDA_MAX - LAMBDA_MIN) * 10.0 - 0.5;
+        let index = f32::clamp(f32::floor(position), 0.0, 8.0) as usize;
+        let t = f32::clamp(position - index as f32, 0.0, 1.0);
+
+        return spectrum[index] + (spectrum[index + 1] - spectrum[index]) * t;
+    };
+
+    let value = if r <= g && r <= b
+    {
+        let rest = if g <= b { (g - r) * basis(&CYAN) + (b - g) * basis(&BLUE) } else { (b - r) * basis(&CYAN) + (g - b) * basis(&GREEN) };
+        r * basis(&WHITE) + rest
+    }
+
+    else if g <= r && g <= b
+    {
+        let rest = if r <= b { (r - g) * basis(&MAGENTA) + (b - r) * basis(&BLUE) } else { (b - g) * basis(&MAGENTA) + (r - b) * basis(&RED) };
+        g * basis(&WHITE) + rest
+    }
+
+    else
+    {
+        let rest = if r <= g { (r - b) * basis(&YELLOW) + (g - r) * basis(&GREEN) } else { (g - b) * basis(&YELLOW) + (r - g) * basis(&RED) };
+        b * basis(&WHITE) + rest
+    };
+
+    return f32::max(value, 0.0);
+}
+
+// cie 1931 colour matching functions, from the piecewise gaussian fit of wyman, sloan and shirley (2013)
+pub fn cie_xyz(lambda: f32) -> (f32, f32, f32)
+{
+    let g = |mu: f32, below: f32, above: f32|
+    {
+        let t = (lambda - mu) / if lambda < mu { below } else { above };
+        return f32::exp(-0.5 * t * t);
+    };
+
+    let x = 1.056 * g(599.8, 37.9, 31.0) + 0.362 * g(442.0, 16.0, 26.7) - 0.065 * g(501.1, 20.4, 26.2);
+    let y = 0.821 * g(568.8, 46.9, 40.5) + 0.286 * g(530.9, 16.3, 31.1);
+    let z = 1.217 * g(437.0, 11.8, 36.0) + 0.681 * g(459.0, 26.0, 13.8);
+
+    return (x, y, z);
+}
+
+struct Integrals
+{
+    // integral of the y matching function over the sampled range
+    y: f32,
+
+    // rgb of a constant 1 spectrum once normalized by y, used to white balance
+    white: [f32; 3],
+}
+
+fn integrals() -> &'static Integrals
+{
+    static INTEGRALS: OnceLock<Integrals> = OnceLock::new();
+
+    return INTEGRALS.get_or_init(||
+    {
+        let mut xyz = [0.0; 3];
+        let steps = (LAMBDA_MAX - LAMBDA_MIN) as usize;
+
+        // midpoint rule at 1nm spacing
+        for step in 0..steps
+        {
+            let (x, y, z) = cie_xyz(LAMBDA_MIN + step as f32 + 0.5);
+
+            xyz[0] += x;
+            xyz[1] += y;
+            xyz[2] += z;
+        }
+
+        let white = ColorSpace::LinearSrgb.from_xyz(&Vector::new(xyz[0] / xyz[1], 1.0, xyz[2] / xyz[1], 0.0));
+        return Integrals{ y: xyz[1], white: [white.x(), white.y(), white.z()] };
+    });
+}
@@ -0,0 +1,350 @@
+// (c) 2025 Connor J. Link. All Rights Reserved.
+// Luma - types.rs
+
+use crate::vector::*;
+use crate::matrix::*;
+
+// a location, stored with w = 1 so that transforms translate it
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Point(Vector);
+
+// an offset or heading, stored with w = 0 so that transforms ignore translation
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Direction(Vector);
+
+// a surface orientation, which transforms by the inverse-transpose to stay perpendicular under non-uniform scale
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Normal(Vector);
+
+impl Point
+{
+    pub fn new(x: f32, y: f32, z: f32) -> Point
+    {
+        return Point(Vector::new(x, y, z, 1.0));
+    }
+
+    pub fn origin() -> Point
+    {
+        return Point::new(0.0, 0.0, 0.0);
+    }
+
+    pub fn x(&self) -> f32
+    {
+        return self.0.x();
+    }
+
+    pub fn y(&self) -> f32
+    {
+        return self.0.y();
+    }
+
+    pub fn z(&self) -> f32
+    {
+        return self.0.z();
+    }
+
+    pub fn vector(&self) -> Vector
+    {
+        return self.0;
+    }
+
+    pub fn distance(point1: &Point, point2: &Point) -> f32
+    {
+        return (*point2 - *point1).length();
+    }
+
+    pub fn lerp(point1: &Point, point2: &Point, t: f32) -> Point
+    {
+        return *point1 + (*point2 - *point1) * t;
+    }
+
+    pub fn transform(&self, mat1: &Matrix) -> Point
+    {
+        return Point::from(Matrix::transform_point(mat1, &self.0));
+    }
+}
+
+impl Direction
+{
+    pub fn new(x: f32, y: f32, z: f32) -> Direction
+    {
+        return Direction(Vector::new(x, y, z, 0.0));
+    }
+
+    pub fn zero() -> Direction
+    {
+        return Direction::new(0.0, 0.0, 0.0);
+    }
+
+    pub fn x(&self) -> f32
+    {
+        return self.0.x();
+    }
+
+    pub fn y(&self) -> f32
+    {
+        return self.0.y();
+    }
+
+    pub fn z(&self) -> f32
+    {
+        return self.0.z();
+    }
+
+    pub fn vector(&self) -> Vector
+    {
+        return self.0;
+    }
+
+    pub fn dot(&self, other: &Direction) -> f32
+    {
+        return Vector::dot(&self.0, &other.0);
+    }
+
+    pub fn cross(&self, other: &Direction) -> Direction
+    {
+        return Direction(Vector::cross(&self.0, &other.0));
+    }
+
+    pub fn length(&self) -> f32
+    {
+        return Vector::length(&self.0);
+    }
+
+    pub fn normalize(&self) -> Direction
+    {
+        return Direction(Vector::normalize(&self.0));
+    }
+
+    pub fn reflect(&self, normal: &Normal) -> Direction
+    {
+        return Direction(Vector::reflect(&self.0, &normal.0));
+    }
+
+    pub fn refract(&self, normal: &Normal, eta: f32) -> Option<Direction>
+    {
+        return Vector::refract(&self.0, &normal.0, eta).map(Direction);
+    }
+
+    pub fn transform(&self, mat1: &Matrix) -> Direction
+    {
+        return Direction::from(Matrix::transform_direction(mat1, &self.0));
+    }
+}
+
+impl Normal
+{
+    // normals are always kept at unit length
+    pub fn new(x: f32, y: f32, z: f32) -> Normal
+    {
+        return Normal(Vector::normalize(&Vector::new(x, y, z, 0.0)));
+    }
+
+    pub fn x(&self) -> f32
+    {
+        return self.0.x();
+    }
+
+    pub fn y(&self) -> f32
+    {
+        return self.0.y();
+    }
+
+    pub fn z(&self) -> f32
+    {
+        return self.0.z();
+    }
+
+    pub fn vector(&self) -> Vector
+    {
+        return self.0;
+    }
+
+    pub fn dot(&self, direction: &Direction) -> f32
+    {
+        return Vector::dot(&self.0, &direction.0);
+    }
+
+    // takes the inverse of the transform applied to the surface
+    pub fn transform(&self, inverse: &Matrix) -> Normal
+    {
+        return Normal::from(Matrix::transform_normal(inverse, &self.0));
+    }
+
+    // flips the normal to face against the given direction
+    pub fn facing(&self, direction: &Direction) -> Normal
+    {
+        if self.dot(direction) > 0.0
+        {
+            return -*self;
+        }
+
+        return *self;
+    }
+}
+
+// conversions from the raw vector pin down w, so a stray homogeneous component never leaks through
+impl From<Vector> for Point
+{
+    fn from(vec1: Vector) -> Point
+    {
+        return Point::new(vec1.x(), vec1.y(), vec1.z());
+    }
+}
+
+impl From<Vector> for Direction
+{
+    fn from(vec1: Vector) -> Direction
+    {
+        return Direction::new(vec1.x(), vec1.y(), vec1.z());
+    }
+}
+
+impl From<Vector> for Normal
+{
+    fn from(vec1: Vector) -> Normal
+    {
+        return Normal::new(vec1.x(), vec1.y(), vec1.z());
+    }
+}
+
+impl From<Direction> for Normal
+{
+    fn from(direction: Direction) -> Normal
+    {
+        return Normal::from(direction.0);
+    }
+}
+
+impl From<Normal> for Direction
+{
+    fn from(normal: Normal) -> Direction
+    {
+        return Direction(normal.0);
+    }
+}
+
+impl From<Point> for Vector
+{
+    fn from(point: Point) -> Vector
+    {
+        return point.0;
+    }
+}
+
+impl From<Direction> for Vector
+{
+    fn from(direction: Direction) -> Vector
+    {
+        return direction.0;
+    }
+}
+
+impl From<Normal> for Vector
+{
+    fn from(normal: Normal) -> Vector
+    {
+        return normal.0;
+    }
+}
+
+// affine combinations: only differences of points and offsets of points make sense
+
+impl std::ops::Sub for Point
+{
+    type Output = Direction;
+
+    fn sub(self, other: Point) -> Direction
+    {
+        return Direction::from(self.0 - other.0);
+    }
+}
+
+impl std::ops::Add<Direction> for Point
+{
+    type Output = Point;
+
+    fn add(self, direction: Direction) -> Point
+    {
+        return Point::from(self.0 + direction.0);
+    }
+}
+
+impl std::ops::Sub<Direction> for Point
+{
+    type Output = Point;
+
+    fn sub(self, direction: Direction) -> Point
+    {
+        return Point::from(self.0 - direction.0);
+    }
+}
+
+impl std::ops::AddAssign<Direction> for Point
+{
+    fn add_assign(&mut self, direction: Direction)
+    {
+        *self = *self + direction;
+    }
+}
+
+impl std::ops::Add for Direction
+{
+    type Output = Direction;
+
+    fn add(self, other: Direction) -> Direction
+    {
+        return Direction(self.0 + other.0);
+    }
+}
+
+impl std::ops::Sub for Direction
+{
+    type Output = Direction;
+
+    fn sub(self, other: Direction) -> Direction
+    {
+        return Direction(self.0 - other.0);
+    }
+}
+
+impl std::ops::Mul<f32> for Direction
+{
+    type Output = Direction;
+
+    fn mul(self, scalar: f32) -> Direction
+    {
+        return Direction(self.0 * scalar);
+    }
+}
+
+impl std::ops::Neg for Direction
+{
+    type Output = Direction;
+
+    fn neg(self) -> Direction
+    {
+        return Direction(-self.0);
+    }
+}
+
+impl std::ops::Neg for Normal
+{
+    type Output = Normal;
+
+    fn neg(self) -> Normal
+    {
+        return Normal(-self.0);
+    }
+}
+
+// scaling a normal gives an offset along it, such as a ray spawn epsilon
+impl std::ops::Mul<f32> for Normal
+{
+    type Output = Direction;
+
+    fn mul(self, scalar: f32) -> Direction
+    {
+        return Direction(self.0 * scalar);
+    }
+}
@@ -1,8 +1,10 @@
 // (c) 2025 Connor J. Link. All Rights Reserved.
 // Luma - Vector.rs
 
+use crate::simd;
+
 #[repr(C)]
-#[derive(Copy, Clone, Debug, bytemuck::Pod, bytemuck::Zeroable)]
+#[derive(Copy, Clone, Debug, PartialEq, bytemuck::Pod, bytemuck::Zeroable)]
 pub struct Vector
 {
     x: f32,
@@ -18,6 +20,11 @@ impl Vector
         return [self.x, self.y, self.z, self.w];
     }
 
+    pub fn from_raw(raw: [f32; 4]) -> Vector
+    {
+        return Vector{ x: raw[0], y: raw[1], z: raw[2], w: raw[3] };
+    }
+
     pub fn x(&self) -> f32
     {
         return self.x;
@@ -85,22 +92,22 @@ impl Vector
 
     pub fn add(vec1: &Vector, vec2: &Vector) -> Vector
     { 
-        return Vector{ x: vec1.x + vec2.x, y: vec1.y + vec2.y, z: vec1.z + vec2.z, w: vec1.w + vec2.w };
+        return Vector::from_raw(simd::add(vec1.raw(), vec2.raw()));
     }
 
     pub fn sub(vec1: &Vector, vec2: &Vector) -> Vector
     {
-        return Vector{ x: vec1.x - vec2.x, y: vec1.y - vec2.y, z: vec1.z - vec2.z, w: vec1.w - vec2.w };
+        return Vector::from_raw(simd::sub(vec1.raw(), vec2.raw()));
     }
 
     pub fn mul(vec1: &Vector, vec2: &Vector) -> Vector
     {
-        return Vector{ x: vec1.x * vec2.x, y: vec1.y * vec2.y, z: vec1.z * vec2.z, w: vec1.w * vec2.w };
+        return Vector::from_raw(simd::mul(vec1.raw(), vec2.raw()));
     }
 
     pub fn scale(vec1: &Vector, scalar: f32) -> Vector
     {
-        return Vector{ x: vec1.x * scalar, y: vec1.y * scalar, z: vec1.z * scalar, w: vec1.w * scalar };
+        return Vector::from_raw(simd::scale(vec1.raw(), scalar));
     }
 
     pub fn dot(vec1: &Vector, vec2: &Vector) -> f32
@@ -147,5 +154,129 @@ impl Vector
         return Vector::sub(vec1, &Vector::scale(normal, 2.0 * dot_product));
     }
 
-    // TODO: refraction
-}
\ No newline at end of file
+    // snell's law for a unit direction and a unit normal facing against it, with eta the ratio of the incident
+    // to the transmitted index of refraction, or none under total internal reflection
+    pub fn refract(vec1: &Vector, normal: &Vector, eta: f32) -> Option<Vector>
+    {
+        let cos_incident = -Vector::dot(vec1, normal);
+        let sin2_transmitted = eta * eta * (1.0 - cos_incident * cos_incident);
+
+        if sin2_transmitted > 1.0
+        {
+            return None;
+        }
+
+        let cos_transmitted = f32::sqrt(1.0 - sin2_transmitted);
+        return Some(Vector::add(&Vector::scale(vec1, eta), &Vector::scale(normal, eta * cos_incident - cos_transmitted)));
+    }
+}
+impl std::ops::Add for Vector
+{
+    type Output = Vector;
+
+    fn add(self, other: Vector) -> Vector
+    {
+        return Vector::add(&self, &other);
+    }
+}
+
+impl std::ops::Sub for Vector
+{
+    type Output = Vector;
+
+    fn sub(self, other: Vector) -> Vector
+    {
+        return Vector::sub(&self, &other);
+    }
+}
+
+// componentwise product, as used for colors
+impl std::ops::Mul for Vector
+{
+    type Output = Vector;
+
+    fn mul(self, other: Vector) -> Vector
+    {
+        return Vector::mul(&self, &other);
+    }
+}
+
+impl std::ops::Mul<f32> for Vector
+{
+    type Output = Vector;
+
+    fn mul(self, scalar: f32) -> Vector
+    {
+        return Vector::scale(&self, scalar);
+    }
+}
+
+impl std::ops::Mul<Vector> for f32
+{
+    type Output = Vector;
+
+    fn mul(self, vector: Vector) -> Vector
+    {
+        return Vector::scale(&vector, self);
+    }
+}
+
+impl std::ops::Div<f32> for Vector
+{
+    type Output = Vector;
+
+    fn div(self, scalar: f32) -> Vector
+    {
+        return Vector::scale(&self, 1.0 / scalar);
+    }
+}
+
+impl std::ops::Neg for Vector
+{
+    type Output = Vector;
+
+    fn neg(self) -> Vector
+    {
+        return Vector::scale(&self, -1.0);
+    }
+}
+
+impl std::ops::AddAssign for Vector
+{
+    fn add_assign(&mut self, other: Vector)
+    {
+        *self = Vector::add(self, &other);
+    }
+}
+
+impl std::ops::SubAssign for Vector
+{
+    fn sub_assign(&mut self, other: Vector)
+    {
+        *self = Vector::sub(self, &other);
+    }
+}
+
+impl std::ops::MulAssign<f32> for Vector
+{
+    fn mul_assign(&mut self, scalar: f32)
+    {
+        *self = Vector::scale(self, scalar);
+    }
+}
+
+impl From<nalgebra_glm::Vec4> for Vector
+{
+    fn from(vec1: nalgebra_glm::Vec4) -> Vector
+    {
+        return Vector{ x: vec1.x, y: vec1.y, z: vec1.z, w: vec1.w };
+    }
+}
+
+impl From<Vector> for nalgebra_glm::Vec4
+{
+    fn from(vec1: Vector) -> nalgebra_glm::Vec4
+    {
+        return nalgebra_glm::vec4(vec1.x, vec1.y, vec1.z, vec1.w);
+    }
+}
@@ -0,0 +1,135 @@
+// (c) 2025 Connor J. Link. All Rights Reserved.
+// Luma - world.rs
+
+use crate::vector::*;
+
+// an absolute position in double precision. f32 keeps about seven significant digits, so a kilometre from the
+// origin positions are only good to a few tenths of a millimetre, and the error grows with distance until
+// reflections and camera motion visibly jitter. rendering only ever sees offsets from a nearby origin, in f32
+#[derive(Clone)]
+#[derive(Copy)]
+#[derive(Debug)]
+#[derive(PartialEq)]
+pub struct WorldPosition
+{
+    pub x: f64,
+    pub y: f64,
+    pub z: f64,
+}
+
+impl WorldPosition
+{
+    pub fn new(x: f64, y: f64, z: f64) -> WorldPosition
+    {
+        return WorldPosition{ x: x, y: y, z: z };
+    }
+
+    pub fn origin() -> WorldPosition
+    {
+        return WorldPosition{ x: 0.0, y: 0.0, z: 0.0 };
+    }
+
+    pub fn from_vector(vec1: &Vector) -> WorldPosition
+    {
+        return WorldPosition{ x: vec1.x() as f64, y: vec1.y() as f64, z: vec1.z() as f64 };
+    }
+
+    // lossy far from the origin, only meant for storage formats and display
+    pub fn to_vector(&self) -> Vector
+    {
+        return Vector::new(self.x as f32, self.y as f32, self.z as f32, 1.0);
+    }
+
+    // the offset from an origin, subtracted in double precision before rounding so nearby offsets stay exact
+    pub fn relative(&self, origin: &WorldPosition) -> Vector
+    {
+        return Vector::new((self.x - origin.x) as f32, (self.y - origin.y) as f32, (self.z - origin.z) as f32, 1.0);
+    }
+
+    pub fn offset(&self, vec1: &Vector) -> WorldPosition
+    {
+        return WorldPosition{ x: self.x + vec1.x() as f64, y: self.y + vec1.y() as f64, z: self.z + vec1.z() as f64 };
+    }
+
+    pub fn lerp(position1: &WorldPosition, position2: &WorldPosition, t: f32) -> WorldPosition
+    {
+        let t = t as f64;
+
+        return WorldPosition
+        {
+            x: position1.x + (position2.x - position1.x) * t,
+            y: position1.y + (position2.y - position1.y) * t,
+            z: position1.z + (position2.z - position1.z) * t,
+        };
+    }
+
+    pub fn distance(position1: &WorldPosition, position2: &WorldPosition) -> f64
+    {
+        let (x, y, z) = (position1.x - position2.x, position1.y - position2.y, position1.z - position2.z);
+        return f64::sqrt(x * x + y * y + z * z);
+    }
+}
+
+// camera-relative rendering: everything handed to the renderer is expressed relative to an origin that follows the
+// camera, moved in steps once the camera strays past the threshold so the scene isn't rebuilt every frame
+#[derive(Clone)]
+#[derive(Copy)]
+#[derive(Debug)]
+pub struct FloatingOrigin
+{
+    pub enabled: bool,
+    pub threshold: f32,
+    origin: WorldPosition,
+}
+
+impl FloatingOrigin
+{
+    pub fn new() -> FloatingOrigin
+    {
+        return FloatingOrigin
+        {
+            enabled: true,
+            threshold: 256.0,
+            origin: WorldPosition::origin(),
+        };
+    }
+
+    pub fn origin(&self) -> WorldPosition
+    {
+        return self.origin;
+    }
+
+    pub fn to_local(&self, position: &WorldPosition) -> Vector
+    {
+        return position.relative(&self.origin);
+    }
+
+    pub fn to_world(&self, local: &Vector) -> WorldPosition
+    {
+        return self.origin.offset(local);
+    }
+
+    // how far everything in local space has to move, if the camera at this local position warrants a rebase.
+    // the origin snaps to the camera so a single shift brings it back to the middle of the precise region
+    pub fn rebase(&mut self, camera: &Vector) -> Option<Vector>
+    {
+        if !self.enabled || Vector::length(&Vector::new(camera.x(), camera.y(), camera.z(), 0.0)) < self.threshold
+        {
+            return None;
+        }
+
+        let shift = Vector::new(camera.x(), camera.y(), camera.z(), 0.0);
+        self.origin = self.origin.offset(&shift);
+
+        return Some(shift);
+    }
+
+    // back to world space as the local space, for when camera-relative rendering is switched off
+    pub fn reset(&mut self) -> Vector
+    {
+        let shift = Vector::new(-self.origin.x as f32, -self.origin.y as f32, -self.origin.z as f32, 0.0);
+        self.origin = WorldPosition::origin();
+
+        return shift;
+    }
+}